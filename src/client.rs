@@ -11,6 +11,7 @@ use reqwest::blocking::{Client, RequestBuilder};
 pub use reqwest::Body;
 use reqwest::{IntoUrl, Method, Url};
 
+use crate::data::{ApiBackend, DataCache, StorageBackend};
 use crate::error::{Error, ResultExt};
 
 struct Simple(HeaderValue);
@@ -54,12 +55,15 @@ pub struct HttpClient {
     api_auth: ApiAuth,
     inner_client: Arc<Client>,
     user_agent: String,
+    cache: Option<Arc<DataCache>>,
+    // Only `None` in the client held by the Data API backend itself
+    backend: Option<Arc<dyn StorageBackend>>,
 }
 
 impl HttpClient {
     /// Instantiate an `HttpClient` - creates a new `reqwest` client
     pub fn new<U: IntoUrl>(api_auth: ApiAuth, base_url: U) -> Result<HttpClient, Error> {
-        Ok(HttpClient {
+        let mut client = HttpClient {
             api_auth: api_auth,
             base_url: base_url.into_url().context("Invalid base URL")?,
             inner_client: Self::inner_client(),
@@ -67,8 +71,35 @@ impl HttpClient {
                 "algorithmia-rust/{}",
                 option_env!("CARGO_PKG_VERSION").unwrap_or("unknown"),
             ),
-        })
+            cache: None,
+            backend: None,
+        };
+        client.backend = Some(Arc::new(ApiBackend::new(client.clone())));
+        Ok(client)
     }
+
+    /// Cache of downloaded data files, if configured
+    pub fn cache(&self) -> Option<&Arc<DataCache>> {
+        self.cache.as_ref()
+    }
+
+    /// Configure the cache of downloaded data files
+    pub fn set_cache(&mut self, cache: Option<Arc<DataCache>>) {
+        self.cache = cache;
+    }
+    /// Storage backend for data operations (the Data API unless configured otherwise)
+    pub(crate) fn backend(&self) -> Arc<dyn StorageBackend> {
+        match &self.backend {
+            Some(backend) => backend.clone(),
+            None => unreachable!("the Data API backend does not use its own client's backend"),
+        }
+    }
+
+    /// Configure the storage backend for data operations
+    pub fn set_backend(&mut self, backend: Arc<dyn StorageBackend>) {
+        self.backend = Some(backend);
+    }
+
     /// Helper to make Algorithmia GET requests with the API key
     pub fn get(&self, url: Url) -> RequestBuilder {
         self.build_request(Method::GET, url)
@@ -84,6 +115,11 @@ impl HttpClient {
         self.build_request(Method::POST, url)
     }
 
+    /// Helper to make Algorithmia PATCH requests with the API key
+    pub fn patch(&self, url: Url) -> RequestBuilder {
+        self.build_request(Method::PATCH, url)
+    }
+
     /// Helper to make Algorithmia PUT requests with the API key
     pub fn put(&self, url: Url) -> RequestBuilder {
         self.build_request(Method::PUT, url)
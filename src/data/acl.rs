@@ -0,0 +1,264 @@
+//! Principals and builder for composing `DataAcl` permissions
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use algorithmia::Algorithmia;
+//! use algorithmia::data::DataAcl;
+//!
+//! let client = Algorithmia::client("111112222233333444445555566")?;
+//! let acl = DataAcl::builder()
+//!     .my_algorithms()
+//!     .user("alice")
+//!     .org("acme")
+//!     .algorithm("bob/Classifier/*")
+//!     .build()?;
+//! client.dir(".my/shared").set_acl(acl)?;
+//! # Ok::<(), Box<std::error::Error>>(())
+//! ```
+
+use crate::data::{DataAcl, ReadAcl};
+use crate::error::{err_msg, Error};
+use std::fmt;
+use std::str::FromStr;
+
+/// A principal that may be granted read access to a `DataDir`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AclPrincipal {
+    /// Any user (`user://*`)
+    AnyUser,
+    /// A specific user (`user://<username>`)
+    User(String),
+    /// Members of an organization (`org://<orgname>`)
+    Org(String),
+    /// Algorithms matching an `owner/algorithm/version` glob (`algo://<glob>`)
+    ///
+    /// The owner may be `.my` for the directory owner's algorithms, and any
+    /// trailing segment may be `*`, e.g. `.my/*` or `alice/Classifier/*`.
+    Algorithm(String),
+}
+
+impl AclPrincipal {
+    /// Algorithms owned by the owner of the directory (`algo://.my/*`)
+    pub fn my_algorithms() -> AclPrincipal {
+        AclPrincipal::Algorithm(".my/*".into())
+    }
+
+    /// Check that the principal names a valid user, organization or algorithm glob
+    pub fn validate(&self) -> Result<(), Error> {
+        match self {
+            AclPrincipal::AnyUser => Ok(()),
+            AclPrincipal::User(name) => validate_name("username", name),
+            AclPrincipal::Org(name) => validate_name("organization name", name),
+            AclPrincipal::Algorithm(glob) => validate_algo_glob(glob),
+        }
+    }
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '-'
+}
+
+fn validate_name(kind: &str, name: &str) -> Result<(), Error> {
+    if name.is_empty() || !name.chars().all(is_name_char) {
+        bail!("invalid {} in ACL: '{}'", kind, name);
+    }
+    Ok(())
+}
+
+fn validate_algo_glob(glob: &str) -> Result<(), Error> {
+    let segments: Vec<&str> = glob.split('/').collect();
+    if segments.len() > 3 {
+        bail!("invalid algorithm in ACL: '{}' has too many segments", glob);
+    }
+    for (i, segment) in segments.iter().enumerate() {
+        let valid = match *segment {
+            "*" => i == segments.len() - 1,
+            ".my" => i == 0,
+            // Versions may contain dots, e.g. `1.0.2`
+            s if i == 2 => !s.is_empty() && s.chars().all(|c| is_name_char(c) || c == '.'),
+            s => !s.is_empty() && s.chars().all(is_name_char),
+        };
+        if !valid {
+            bail!("invalid algorithm in ACL: '{}'", glob);
+        }
+    }
+    Ok(())
+}
+
+impl fmt::Display for AclPrincipal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AclPrincipal::AnyUser => f.write_str("user://*"),
+            AclPrincipal::User(name) => write!(f, "user://{}", name),
+            AclPrincipal::Org(name) => write!(f, "org://{}", name),
+            AclPrincipal::Algorithm(glob) => write!(f, "algo://{}", glob),
+        }
+    }
+}
+
+impl FromStr for AclPrincipal {
+    type Err = Error;
+    fn from_str(principal: &str) -> Result<Self, Self::Err> {
+        let mut parts = principal.splitn(2, "://");
+        let principal = match (parts.next(), parts.next()) {
+            (Some("user"), Some("*")) => AclPrincipal::AnyUser,
+            (Some("user"), Some(name)) => AclPrincipal::User(name.into()),
+            (Some("org"), Some(name)) => AclPrincipal::Org(name.into()),
+            (Some("algo"), Some(glob)) => AclPrincipal::Algorithm(glob.into()),
+            _ => {
+                return Err(err_msg(format!(
+                    "unrecognized ACL principal '{}'",
+                    principal
+                )))
+            }
+        };
+        principal.validate()?;
+        Ok(principal)
+    }
+}
+
+impl DataAcl {
+    /// Start composing an ACL that grants read access to specific principals
+    ///
+    /// An ACL built without adding any principals is private.
+    pub fn builder() -> AclBuilder {
+        AclBuilder {
+            principals: Vec::new(),
+        }
+    }
+
+    /// Parse the read ACL into principals
+    ///
+    /// Fails if any entry is not a recognized principal.
+    pub fn principals(&self) -> Result<Vec<AclPrincipal>, Error> {
+        self.read.iter().map(|p| p.parse()).collect()
+    }
+}
+
+/// Builder for a `DataAcl` granting read access to users, organizations and algorithms
+#[derive(Debug, Clone)]
+pub struct AclBuilder {
+    principals: Vec<AclPrincipal>,
+}
+
+impl AclBuilder {
+    /// Grant read access to a principal
+    pub fn grant(&mut self, principal: AclPrincipal) -> &mut AclBuilder {
+        self.principals.push(principal);
+        self
+    }
+
+    /// Grant read access to any user
+    pub fn any_user(&mut self) -> &mut AclBuilder {
+        self.grant(AclPrincipal::AnyUser)
+    }
+
+    /// Grant read access to a specific user
+    pub fn user<S: Into<String>>(&mut self, username: S) -> &mut AclBuilder {
+        self.grant(AclPrincipal::User(username.into()))
+    }
+
+    /// Grant read access to members of an organization
+    pub fn org<S: Into<String>>(&mut self, org: S) -> &mut AclBuilder {
+        self.grant(AclPrincipal::Org(org.into()))
+    }
+
+    /// Grant read access to algorithms matching an `owner/algorithm/version` glob
+    pub fn algorithm<S: Into<String>>(&mut self, glob: S) -> &mut AclBuilder {
+        self.grant(AclPrincipal::Algorithm(glob.into()))
+    }
+
+    /// Grant read access to the directory owner's algorithms
+    pub fn my_algorithms(&mut self) -> &mut AclBuilder {
+        self.grant(AclPrincipal::my_algorithms())
+    }
+
+    /// Validate the granted principals and build the `DataAcl`
+    pub fn build(&self) -> Result<DataAcl, Error> {
+        let mut read: Vec<String> = Vec::new();
+        for principal in &self.principals {
+            principal.validate()?;
+            let principal = principal.to_string();
+            if !read.contains(&principal) {
+                read.push(principal);
+            }
+        }
+        Ok(ReadAcl::Custom(read).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_principals() {
+        assert_eq!(
+            "user://*".parse::<AclPrincipal>().unwrap(),
+            AclPrincipal::AnyUser
+        );
+        assert_eq!(
+            "user://alice".parse::<AclPrincipal>().unwrap(),
+            AclPrincipal::User("alice".into())
+        );
+        assert_eq!(
+            "org://acme".parse::<AclPrincipal>().unwrap(),
+            AclPrincipal::Org("acme".into())
+        );
+        assert_eq!(
+            "algo://.my/*".parse::<AclPrincipal>().unwrap(),
+            AclPrincipal::my_algorithms()
+        );
+        assert_eq!(
+            "algo://bob/Classifier/1.0.2"
+                .parse::<AclPrincipal>()
+                .unwrap(),
+            AclPrincipal::Algorithm("bob/Classifier/1.0.2".into())
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_principals() {
+        assert!("alice".parse::<AclPrincipal>().is_err());
+        assert!("group://admins".parse::<AclPrincipal>().is_err());
+        assert!("user://".parse::<AclPrincipal>().is_err());
+        assert!("user://al ice".parse::<AclPrincipal>().is_err());
+        assert!("algo://*/Foo".parse::<AclPrincipal>().is_err());
+        assert!("algo://bob/.my".parse::<AclPrincipal>().is_err());
+        assert!("algo://bob/Foo/1.0/extra".parse::<AclPrincipal>().is_err());
+    }
+
+    #[test]
+    fn test_builder() {
+        let acl = DataAcl::builder()
+            .my_algorithms()
+            .user("alice")
+            .org("acme")
+            .user("alice")
+            .build()
+            .unwrap();
+        assert_eq!(acl.read, vec!["algo://.my/*", "user://alice", "org://acme"]);
+
+        assert!(DataAcl::builder().user("not valid").build().is_err());
+        assert_eq!(DataAcl::builder().build().unwrap(), ReadAcl::Private.into());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let acl = DataAcl::builder()
+            .any_user()
+            .algorithm("bob/Classifier/*")
+            .build()
+            .unwrap();
+        let json = serde_json::to_string(&acl).unwrap();
+        let decoded: DataAcl = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            decoded.principals().unwrap(),
+            vec![
+                AclPrincipal::AnyUser,
+                AclPrincipal::Algorithm("bob/Classifier/*".into())
+            ]
+        );
+    }
+}
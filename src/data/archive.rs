@@ -0,0 +1,516 @@
+//! Transferring directory trees as tar.gz or zip archives
+//!
+//! Uploading a directory as a single archive file takes one request,
+//! instead of one per file. Requires the `archive` feature.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use algorithmia::Algorithmia;
+//! use algorithmia::data::ArchiveFormat;
+//!
+//! let client = Algorithmia::client("111112222233333444445555566")?;
+//! let dataset = client.file(".my/datasets/images.tar.gz");
+//!
+//! // Pack and upload a local directory
+//! dataset.put_archive("/path/to/images", ArchiveFormat::TarGz)?;
+//!
+//! // Download and unpack it somewhere else
+//! dataset.extract_to("/tmp/images", ArchiveFormat::TarGz)?;
+//! # Ok::<(), Box<std::error::Error>>(())
+//! ```
+
+use crate::data::temp::create_spool_file;
+use crate::data::{DataDir, DataFile, DataItem, FileData, HasDataPath};
+use crate::error::{Error, ResultExt};
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// Called with the archive path of each file (with its content) and directory (with `None`)
+type Visitor<'a> = dyn FnMut(&str, Option<FileData>) -> Result<(), Error> + 'a;
+
+/// Archive format for transferring directory trees
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    /// Gzip-compressed tar archive
+    TarGz,
+    /// Zip archive with deflate-compressed entries
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// File extension (without the leading dot) conventionally used for this format
+    pub fn extension(self) -> &'static str {
+        match self {
+            ArchiveFormat::TarGz => "tar.gz",
+            ArchiveFormat::Zip => "zip",
+        }
+    }
+
+    /// Detect the format from the extension of a file name (`.tar.gz`, `.tgz`, or `.zip`)
+    pub fn for_name(name: &str) -> Option<ArchiveFormat> {
+        let name = name.to_lowercase();
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(ArchiveFormat::TarGz)
+        } else if name.ends_with(".zip") {
+            Some(ArchiveFormat::Zip)
+        } else {
+            None
+        }
+    }
+}
+
+/// Entry of a local directory tree, with its `/`-separated path relative to the root
+struct LocalEntry {
+    path: PathBuf,
+    name: String,
+    is_dir: bool,
+}
+
+/// Entries of a local directory tree, parents before their children
+fn local_entries(root: &Path) -> Result<Vec<LocalEntry>, Error> {
+    let mut entries = Vec::new();
+    let mut pending = vec![(root.to_path_buf(), String::new())];
+    while let Some((dir, prefix)) = pending.pop() {
+        let mut children = fs::read_dir(&dir)
+            .and_then(|read_dir| read_dir.collect::<io::Result<Vec<_>>>())
+            .with_context(|| format!("error reading directory '{}'", dir.display()))?;
+        children.sort_by_key(|child| child.file_name());
+        for child in children {
+            let name = match child.file_name().into_string() {
+                Ok(name) => format!("{}{}", prefix, name),
+                Err(name) => bail!(
+                    "cannot archive '{}': file name is not valid UTF-8",
+                    dir.join(name).display()
+                ),
+            };
+            let path = child.path();
+            let file_type = child
+                .file_type()
+                .with_context(|| format!("error reading '{}'", path.display()))?;
+            // Following links could archive files outside the tree, or never finish
+            if file_type.is_symlink() {
+                continue;
+            }
+            let is_dir = file_type.is_dir();
+            if is_dir {
+                pending.push((path.clone(), format!("{}/", name)));
+            }
+            entries.push(LocalEntry { path, name, is_dir });
+        }
+    }
+    Ok(entries)
+}
+
+fn zip_options() -> FileOptions {
+    FileOptions::default().compression_method(CompressionMethod::Deflated)
+}
+
+fn write_zip<W: Write + Seek>(writer: W, entries: &[LocalEntry]) -> Result<W, Error> {
+    let mut zip = ZipWriter::new(writer);
+    for entry in entries {
+        if entry.is_dir {
+            zip.add_directory(entry.name.as_str(), zip_options())
+                .with_context(|| format!("error archiving '{}'", entry.path.display()))?;
+        } else {
+            zip.start_file(entry.name.as_str(), zip_options())
+                .with_context(|| format!("error archiving '{}'", entry.path.display()))?;
+            let mut file = File::open(&entry.path)
+                .with_context(|| format!("error reading '{}'", entry.path.display()))?;
+            io::copy(&mut file, &mut zip)
+                .with_context(|| format!("error archiving '{}'", entry.path.display()))?;
+        }
+    }
+    zip.finish().context("error writing zip archive")
+}
+
+/// Unpack the entries of a zip archive into `local_dir`, skipping any outside of it
+fn extract_zip<R: Read + Seek>(mut zip: ZipArchive<R>, local_dir: &Path) -> Result<(), Error> {
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i).context("error reading zip archive")?;
+        let path = match entry.enclosed_name() {
+            Some(name) => local_dir.join(name),
+            None => continue,
+        };
+        if entry.is_dir() {
+            fs::create_dir_all(&path)
+                .with_context(|| format!("error creating directory '{}'", path.display()))?;
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("error creating directory '{}'", parent.display()))?;
+        }
+        let mut file =
+            File::create(&path).with_context(|| format!("error creating '{}'", path.display()))?;
+        io::copy(&mut entry, &mut file)
+            .with_context(|| format!("error extracting '{}'", path.display()))?;
+    }
+    Ok(())
+}
+
+impl DataFile {
+    /// Pack a local directory into an archive and upload it as the content of this file
+    ///
+    /// Tar archives are streamed while they are uploaded. Zip archives are written
+    /// to a temporary local file first, since the format requires seeking.
+    /// Symbolic links in the directory are skipped.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use algorithmia::Algorithmia;
+    /// # use algorithmia::data::ArchiveFormat;
+    /// let client = Algorithmia::client("111112222233333444445555566")?;
+    /// client
+    ///     .file(".my/my_dir/results.zip")
+    ///     .put_archive("/path/to/results", ArchiveFormat::Zip)?;
+    /// # Ok::<(), Box<std::error::Error>>(())
+    /// ```
+    pub fn put_archive<P: AsRef<Path>>(
+        &self,
+        local_dir: P,
+        format: ArchiveFormat,
+    ) -> Result<(), Error> {
+        let local_dir = local_dir.as_ref();
+        let entries = local_entries(local_dir)?;
+        let context = || format!("error archiving '{}'", local_dir.display());
+
+        match format {
+            ArchiveFormat::TarGz => {
+                let mut writer = self.writer();
+                let gz = GzEncoder::new(&mut writer, flate2::Compression::default());
+                let mut tar = tar::Builder::new(gz);
+                for entry in &entries {
+                    if entry.is_dir {
+                        tar.append_dir(&entry.name, &entry.path)
+                    } else {
+                        tar.append_path_with_name(&entry.path, &entry.name)
+                    }
+                    .with_context(context)?;
+                }
+                tar.into_inner()
+                    .and_then(|gz| gz.finish())
+                    .with_context(context)?;
+                writer.finish()
+            }
+            ArchiveFormat::Zip => {
+                let (spool_path, spool) = create_spool_file("archive")
+                    .context("error creating temporary file for zip archive")?;
+                let result = write_zip(spool, &entries).and_then(|spool| {
+                    let len = spool
+                        .metadata()
+                        .with_context(|| format!("error reading '{}'", spool_path.display()))?
+                        .len();
+                    let spool = File::open(&spool_path)
+                        .with_context(|| format!("error reading '{}'", spool_path.display()))?;
+                    self.put_sized(spool, len)
+                });
+                let _ = fs::remove_file(&spool_path);
+                result
+            }
+        }
+    }
+
+    /// Download this archive file and unpack its contents into a local directory
+    ///
+    /// The directory is created if needed. Entries that would be unpacked
+    /// outside of it (e.g. with `..` in their path) are skipped.
+    /// Zip archives are downloaded to a temporary local file first, since the format requires seeking.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use algorithmia::Algorithmia;
+    /// # use algorithmia::data::ArchiveFormat;
+    /// let client = Algorithmia::client("111112222233333444445555566")?;
+    /// client
+    ///     .file(".my/my_dir/model.tar.gz")
+    ///     .extract_to("/tmp/model", ArchiveFormat::TarGz)?;
+    /// # Ok::<(), Box<std::error::Error>>(())
+    /// ```
+    pub fn extract_to<P: AsRef<Path>>(
+        &self,
+        local_dir: P,
+        format: ArchiveFormat,
+    ) -> Result<(), Error> {
+        let local_dir = local_dir.as_ref();
+        fs::create_dir_all(local_dir)
+            .with_context(|| format!("error creating directory '{}'", local_dir.display()))?;
+        let context = || {
+            format!(
+                "error extracting '{}' to '{}'",
+                self.to_data_uri(),
+                local_dir.display()
+            )
+        };
+
+        match format {
+            ArchiveFormat::TarGz => {
+                let data = self.get()?;
+                tar::Archive::new(MultiGzDecoder::new(data))
+                    .unpack(local_dir)
+                    .with_context(context)
+            }
+            ArchiveFormat::Zip => {
+                let (spool_path, spool) = create_spool_file("archive")
+                    .context("error creating temporary file for zip archive")?;
+                let result = self.extract_zip_via(spool, &spool_path, local_dir);
+                let _ = fs::remove_file(&spool_path);
+                result.with_context(context)
+            }
+        }
+    }
+}
+
+impl DataFile {
+    /// Download a zip archive into the exclusively created `spool`, then extract it
+    fn extract_zip_via(
+        &self,
+        mut spool: File,
+        spool_path: &Path,
+        local_dir: &Path,
+    ) -> Result<(), Error> {
+        let metadata = self.head()?;
+        self.download_into(&mut spool, spool_path, &metadata, 0)?;
+        spool
+            .seek(SeekFrom::Start(0))
+            .with_context(|| format!("error reading '{}'", spool_path.display()))?;
+        let zip = ZipArchive::new(spool).context("error reading zip archive")?;
+        extract_zip(zip, local_dir)
+    }
+}
+
+impl DataDir {
+    /// Download the files of this Directory and its subdirectories into a local archive
+    ///
+    /// Each file is still downloaded with its own request, and written into the archive
+    /// as it is downloaded. Returns the number of files archived.
+    /// If any file fails to download, the incomplete archive at `local_path` is removed.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use algorithmia::Algorithmia;
+    /// # use algorithmia::data::ArchiveFormat;
+    /// let client = Algorithmia::client("111112222233333444445555566")?;
+    /// let count = client
+    ///     .dir(".my/my_dir")
+    ///     .download_archive("/tmp/my_dir.tar.gz", ArchiveFormat::TarGz)?;
+    /// println!("archived {} files", count);
+    /// # Ok::<(), Box<std::error::Error>>(())
+    /// ```
+    pub fn download_archive<P: AsRef<Path>>(
+        &self,
+        local_path: P,
+        format: ArchiveFormat,
+    ) -> Result<u64, Error> {
+        let local_path = local_path.as_ref();
+        let file = File::create(local_path)
+            .with_context(|| format!("error writing archive '{}'", local_path.display()))?;
+        let result = self.write_archive(file, local_path, format);
+        if result.is_err() {
+            let _ = fs::remove_file(local_path);
+        }
+        result
+    }
+
+    /// Write the files of this Directory into an archive in `file`, created at `local_path`
+    fn write_archive(
+        &self,
+        file: File,
+        local_path: &Path,
+        format: ArchiveFormat,
+    ) -> Result<u64, Error> {
+        let context = || format!("error writing archive '{}'", local_path.display());
+        match format {
+            ArchiveFormat::TarGz => {
+                let gz = GzEncoder::new(file, flate2::Compression::default());
+                let mut tar = tar::Builder::new(gz);
+                let count = self.walk("", &mut |name, item| {
+                    let mut header = tar::Header::new_gnu();
+                    match item {
+                        Some(data) => {
+                            header.set_entry_type(tar::EntryType::Regular);
+                            header.set_size(data.size);
+                            header.set_mode(0o644);
+                            header.set_mtime(data.last_modified.timestamp().max(0) as u64);
+                            tar.append_data(&mut header, name, data)
+                        }
+                        None => {
+                            header.set_entry_type(tar::EntryType::Directory);
+                            header.set_size(0);
+                            header.set_mode(0o755);
+                            tar.append_data(&mut header, name, io::empty())
+                        }
+                    }
+                    .with_context(context)
+                })?;
+                tar.into_inner()
+                    .and_then(|gz| gz.finish())
+                    .with_context(context)?;
+                Ok(count)
+            }
+            ArchiveFormat::Zip => {
+                let mut zip = ZipWriter::new(file);
+                let count = self.walk("", &mut |name, item| {
+                    match item {
+                        Some(mut data) => {
+                            zip.start_file(name, zip_options()).with_context(context)?;
+                            io::copy(&mut data, &mut zip).with_context(context)?;
+                        }
+                        None => zip
+                            .add_directory(name, zip_options())
+                            .with_context(context)?,
+                    }
+                    Ok(())
+                })?;
+                zip.finish().with_context(context)?;
+                Ok(count)
+            }
+        }
+    }
+
+    /// Visit each file and subdirectory in archive order, returning the number of files
+    fn walk(&self, prefix: &str, visit: &mut Visitor<'_>) -> Result<u64, Error> {
+        let mut count = 0;
+        for item in self.listing() {
+            match item? {
+                DataItem::Dir(d) => {
+                    let name = format!("{}{}/", prefix, d.basename().unwrap_or_default());
+                    visit(&name, None)?;
+                    count += d.walk(&name, visit)?;
+                }
+                DataItem::File(f) => {
+                    let name = format!("{}{}", prefix, f.basename().unwrap_or_default());
+                    visit(&name, Some(f.get()?))?;
+                    count += 1;
+                }
+            }
+        }
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::test_support::LocalTempDir;
+    use crate::data::{DataAcl, MemoryBackend};
+    use crate::Algorithmia;
+
+    fn local_tree(root: &Path) {
+        fs::create_dir_all(root.join("sub")).unwrap();
+        fs::write(root.join("a.txt"), "hello").unwrap();
+        fs::write(root.join("sub/b.txt"), "world").unwrap();
+    }
+
+    #[test]
+    fn test_for_name() {
+        assert_eq!(
+            ArchiveFormat::for_name("x.tar.gz"),
+            Some(ArchiveFormat::TarGz)
+        );
+        assert_eq!(ArchiveFormat::for_name("X.TGZ"), Some(ArchiveFormat::TarGz));
+        assert_eq!(ArchiveFormat::for_name("x.zip"), Some(ArchiveFormat::Zip));
+        assert_eq!(ArchiveFormat::for_name("x.gz"), None);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut client = Algorithmia::client("").unwrap();
+        client.backend(MemoryBackend::new());
+        client
+            .dir(".my/archives")
+            .create(DataAcl::default())
+            .unwrap();
+
+        for &format in &[ArchiveFormat::TarGz, ArchiveFormat::Zip] {
+            let tmp = LocalTempDir::new("archive");
+            let (source, dest) = (tmp.join("tree"), tmp.join("out"));
+            local_tree(&source);
+            let file = client.file(format!(".my/archives/tree.{}", format.extension()).as_str());
+            file.put_archive(&source, format).unwrap();
+
+            file.extract_to(&dest, format).unwrap();
+            assert_eq!(fs::read_to_string(dest.join("a.txt")).unwrap(), "hello");
+            assert_eq!(fs::read_to_string(dest.join("sub/b.txt")).unwrap(), "world");
+        }
+    }
+
+    #[test]
+    fn test_download_archive() {
+        let mut client = Algorithmia::client("").unwrap();
+        client.backend(MemoryBackend::new());
+        client.dir(".my/tree").create(DataAcl::default()).unwrap();
+        client
+            .dir(".my/tree/sub")
+            .create(DataAcl::default())
+            .unwrap();
+        client.file(".my/tree/a.txt").put("hello").unwrap();
+        client.file(".my/tree/sub/b.txt").put("world").unwrap();
+
+        let tmp = LocalTempDir::new("archive");
+        let path = tmp.join("tree.zip");
+        let count = client
+            .dir(".my/tree")
+            .download_archive(&path, ArchiveFormat::Zip)
+            .unwrap();
+        assert_eq!(count, 2);
+
+        let mut zip = ZipArchive::new(File::open(&path).unwrap()).unwrap();
+        let mut content = String::new();
+        zip.by_name("sub/b.txt")
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!(content, "world");
+
+        let missing = tmp.join("missing.tar.gz");
+        assert!(client
+            .dir(".my/missing")
+            .download_archive(&missing, ArchiveFormat::TarGz)
+            .is_err());
+        assert!(!missing.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinks_skipped() {
+        let tmp = LocalTempDir::new("archive");
+        let root = tmp.join("tree");
+        local_tree(&root);
+        std::os::unix::fs::symlink(&root, root.join("sub/loop")).unwrap();
+
+        let names: Vec<_> = local_entries(&root)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.name)
+            .collect();
+        assert_eq!(names, vec!["a.txt", "sub", "sub/b.txt"]);
+    }
+
+    #[test]
+    fn test_extract_skips_outside_entries() {
+        let mut client = Algorithmia::client("").unwrap();
+        client.backend(MemoryBackend::new());
+        let mut zip = ZipWriter::new(io::Cursor::new(Vec::new()));
+        zip.start_file("../outside.txt", zip_options()).unwrap();
+        zip.write_all(b"outside").unwrap();
+        zip.start_file("inside.txt", zip_options()).unwrap();
+        zip.write_all(b"inside").unwrap();
+        let file = client.file(".my/evil.zip");
+        file.put(zip.finish().unwrap().into_inner()).unwrap();
+
+        let tmp = LocalTempDir::new("archive");
+        let dest = tmp.join("out");
+        file.extract_to(&dest, ArchiveFormat::Zip).unwrap();
+        assert_eq!(
+            fs::read_to_string(dest.join("inside.txt")).unwrap(),
+            "inside"
+        );
+        assert!(!tmp.join("outside.txt").exists());
+    }
+}
@@ -0,0 +1,656 @@
+//! Storage backends that data files and directories dispatch through
+//!
+//! By default, `DataFile`, `DataDir`, and `DataObject` operate on the Algorithmia Data API.
+//! Configure a different backend with
+//! [`Algorithmia::backend`](../struct.Algorithmia.html#method.backend) to run the same code
+//! against a local directory ([`LocalBackend`](struct.LocalBackend.html)) or entirely in memory
+//! ([`MemoryBackend`](struct.MemoryBackend.html)), e.g. to develop and test algorithms offline.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use algorithmia::Algorithmia;
+//! use algorithmia::data::LocalBackend;
+//!
+//! let mut client = Algorithmia::client("111112222233333444445555566")?;
+//! // `data://.my/inputs/a.csv` is read from `/tmp/algorithmia-data/.my/inputs/a.csv`
+//! client.backend(LocalBackend::new("/tmp/algorithmia-data"));
+//! let input = client.file(".my/inputs/a.csv").get()?.into_string()?;
+//! # Ok::<(), Box<std::error::Error>>(())
+//! ```
+
+use super::{algo_epoch, parse_headers, response_date, DATA_BASE_PATH};
+use crate::client::header::{lossy_header, X_DATA_TYPE};
+use crate::client::HttpClient;
+use crate::data::{DataAcl, DataType, DataUri, FileData};
+use crate::error::{err_msg, process_http_response, Error, ResultExt};
+use crate::Body;
+use chrono::{DateTime, Utc};
+use http::header::{IF_MATCH, IF_NONE_MATCH, IF_UNMODIFIED_SINCE, RANGE};
+use mime::Mime;
+use reqwest::{StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read};
+
+/// Storage operations that data files and directories are built on
+///
+/// Paths are absolute `DataUri`s. Directories at the root of a connector
+/// and their immediate children (e.g. `data://.my` or an S3 bucket) always exist.
+pub trait StorageBackend: Send + Sync {
+    /// Download the bytes of a file from `start` through `end` (inclusive), or to the end of the file
+    ///
+    /// The `size` of the returned `FileData` is the number of bytes it will read.
+    /// Starting at or past the end of the file reads no bytes.
+    fn get(&self, uri: &DataUri, start: u64, end: Option<u64>) -> Result<FileData, Error>;
+
+    /// Create or replace a file with the content of `body`
+    fn put(&self, uri: &DataUri, body: Body) -> Result<(), Error>;
+
+    /// Create or replace a file with the content of `body` if its current version matches `condition`
+    ///
+    /// Returns whether the file was written. The check must be part of the write,
+    /// so that of several writers expecting the same version only one succeeds.
+    fn put_if(&self, uri: &DataUri, condition: &PutCondition, body: Body) -> Result<bool, Error>;
+
+    /// Delete a file
+    fn delete_file(&self, uri: &DataUri) -> Result<(), Error>;
+
+    /// Move a file to `to` in a single step, replacing any file there
+    ///
+    /// Backends without a native move (such as the Data API) return an error,
+    /// and report it with `supports_rename`.
+    fn rename(&self, from: &DataUri, to: &DataUri) -> Result<(), Error>;
+
+    /// Whether `rename` is a native move, rather than always failing
+    fn supports_rename(&self) -> bool {
+        true
+    }
+
+    /// Get the metadata of a file or directory, or `None` if it does not exist
+    fn head(&self, uri: &DataUri) -> Result<Option<ObjectMetadata>, Error>;
+
+    /// List a page of the contents of a directory
+    ///
+    /// Backends should honor the page size and filter of `options` where they can;
+    /// entries beyond the filter are also removed by the caller.
+    fn list(&self, uri: &DataUri, options: &ListOptions) -> Result<DirectoryPage, Error>;
+
+    /// Create a directory within an existing directory
+    fn mkdir(&self, uri: &DataUri, acl: Option<&DataAcl>) -> Result<(), Error>;
+
+    /// Update the ACL of a directory
+    fn set_acl(&self, uri: &DataUri, acl: &DataAcl) -> Result<(), Error>;
+
+    /// Delete a directory, including its contents if `force` is set
+    ///
+    /// Returns the number of files deleted.
+    fn delete_dir(&self, uri: &DataUri, force: bool) -> Result<u64, Error>;
+}
+
+/// Version a file must have for `StorageBackend::put_if` to write it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PutCondition {
+    /// The file does not exist
+    Absent,
+    /// The file has this entity tag
+    ETag(String),
+    /// The file was last modified at this time, to the precision of the backend's timestamps
+    LastModified(DateTime<Utc>),
+}
+
+impl PutCondition {
+    /// Whether a file with metadata `current` (or `None` if it does not exist) satisfies this condition
+    pub fn matches(&self, current: Option<&ObjectMetadata>) -> bool {
+        match (self, current) {
+            (PutCondition::Absent, current) => current.is_none(),
+            (PutCondition::ETag(etag), Some(current)) => current.etag.as_ref() == Some(etag),
+            (PutCondition::LastModified(time), Some(current)) => {
+                current.last_modified == Some(*time)
+            }
+            (_, None) => false,
+        }
+    }
+}
+
+/// Metadata of a file or directory in a storage backend
+#[derive(Debug, Clone)]
+pub struct ObjectMetadata {
+    /// Whether this is a file or a directory
+    pub data_type: DataType,
+    /// Size of a file in bytes, if known
+    pub size: Option<u64>,
+    /// Last modified timestamp, if known
+    pub last_modified: Option<DateTime<Utc>>,
+    /// Content type of a file, if known
+    pub content_type: Option<Mime>,
+    /// Entity tag identifying this version of a file, if any
+    pub etag: Option<String>,
+}
+
+impl ObjectMetadata {
+    /// Metadata of a file
+    pub fn file(size: u64, last_modified: DateTime<Utc>) -> ObjectMetadata {
+        ObjectMetadata {
+            data_type: DataType::File,
+            size: Some(size),
+            last_modified: Some(last_modified),
+            content_type: None,
+            etag: None,
+        }
+    }
+
+    /// Metadata of a directory
+    pub fn dir() -> ObjectMetadata {
+        ObjectMetadata {
+            data_type: DataType::Dir,
+            size: None,
+            last_modified: None,
+            content_type: None,
+            etag: None,
+        }
+    }
+}
+
+/// Options for listing a page of the contents of a directory
+#[derive(Debug, Clone, Default)]
+pub struct ListOptions {
+    /// Marker returned with the previous page, to continue listing from
+    pub marker: Option<String>,
+    /// Maximum number of entries to return, if limited
+    pub limit: Option<u32>,
+    /// Kinds of entries to return
+    ///
+    /// Backends may return other kinds of entries too (the Data API cannot filter listings),
+    /// so callers filter pages themselves.
+    pub filter: ListFilter,
+    /// Whether to return the ACL of the directory (if ACLs are supported)
+    pub with_acl: bool,
+}
+
+/// Kinds of entries returned by a directory listing
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ListFilter {
+    /// Files and directories
+    #[default]
+    All,
+    /// Only files
+    Files,
+    /// Only directories
+    Folders,
+}
+
+impl ListFilter {
+    /// Whether directories are included
+    pub fn folders(self) -> bool {
+        self != ListFilter::Files
+    }
+
+    /// Whether files are included
+    pub fn files(self) -> bool {
+        self != ListFilter::Folders
+    }
+}
+
+/// Page of the contents of a directory
+#[derive(Debug, Clone, Default)]
+pub struct DirectoryPage {
+    /// ACL of the directory, if requested and supported
+    pub acl: Option<DataAcl>,
+    /// Names of the directories in this page
+    pub folders: Vec<String>,
+    /// Files in this page
+    pub files: Vec<FileEntry>,
+    /// Marker to continue listing from, if there are more pages
+    pub marker: Option<String>,
+}
+
+/// File within a `DirectoryPage`
+#[derive(Debug, Clone)]
+pub struct FileEntry {
+    /// Name of the file within its directory
+    pub name: String,
+    /// Size of the file in bytes
+    pub size: u64,
+    /// Last modified timestamp
+    pub last_modified: DateTime<Utc>,
+}
+
+/// Storage backend for the Algorithmia Data API
+pub(crate) struct ApiBackend {
+    client: HttpClient,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeletedResponse {
+    result: DeletedResult,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeletedResult {
+    deleted: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct FolderItem {
+    pub name: String,
+    pub acl: Option<DataAcl>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FileItem {
+    pub filename: String,
+    pub size: u64,
+    pub last_modified: DateTime<Utc>,
+}
+
+/// Response when querying an existing Directory
+#[derive(Debug, Deserialize)]
+struct DirectoryShow {
+    pub acl: Option<DataAcl>,
+    pub folders: Option<Vec<FolderItem>>,
+    pub files: Option<Vec<FileItem>>,
+    pub marker: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct AclUpdate<'a> {
+    acl: &'a DataAcl,
+}
+
+impl From<DirectoryShow> for DirectoryPage {
+    fn from(show: DirectoryShow) -> DirectoryPage {
+        DirectoryPage {
+            acl: show.acl,
+            folders: show
+                .folders
+                .unwrap_or_default()
+                .into_iter()
+                .map(|folder| folder.name)
+                .collect(),
+            files: show
+                .files
+                .unwrap_or_default()
+                .into_iter()
+                .map(|file| FileEntry {
+                    name: file.filename,
+                    size: file.size,
+                    last_modified: file.last_modified,
+                })
+                .collect(),
+            marker: show.marker,
+        }
+    }
+}
+
+impl ApiBackend {
+    pub(crate) fn new(client: HttpClient) -> ApiBackend {
+        ApiBackend { client }
+    }
+
+    fn url(&self, uri: &DataUri) -> Result<Url, Error> {
+        let path = format!("{}/{}", DATA_BASE_PATH, uri.to_path());
+        self.client
+            .base_url
+            .join(&path)
+            .with_context(|| format!("Failed to construct URL from data URI {}", uri))
+    }
+}
+
+impl StorageBackend for ApiBackend {
+    fn get(&self, uri: &DataUri, start: u64, end: Option<u64>) -> Result<FileData, Error> {
+        let url = self.url(uri)?;
+        let mut req = self.client.get(url);
+        if start > 0 || end.is_some() {
+            let range = match end {
+                Some(end) => format!("bytes={}-{}", start, end),
+                None => format!("bytes={}-", start),
+            };
+            req = req.header(RANGE, range);
+        }
+
+        let res = req
+            .send()
+            .with_context(|| format!("request error downloading file '{}'", uri))?;
+        // The requested range starts at or past the end of the file
+        if res.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+            return Ok(FileData::new(io::empty(), 0, algo_epoch()));
+        }
+        let mut res = process_http_response(res)
+            .with_context(|| format!("response error downloading file '{}'", uri))?;
+
+        let metadata = parse_headers(res.headers())?;
+        let date = response_date(res.headers());
+        match metadata.data_type {
+            DataType::File => (),
+            DataType::Dir => {
+                bail!("expected API response with data type 'file', received 'directory'")
+            }
+        }
+
+        let mut size = metadata.size.unwrap_or(0);
+        let data: Box<dyn Read> = match res.status() {
+            // Server ignored the Range header, so skip and limit the full content ourselves
+            StatusCode::OK if start > 0 || end.is_some() => {
+                io::copy(&mut (&mut res).take(start), &mut io::sink())
+                    .with_context(|| format!("response error downloading file '{}'", uri))?;
+                size = size.saturating_sub(start);
+                match end {
+                    Some(end) => {
+                        size = size.min(end - start + 1);
+                        Box::new(res.take(end - start + 1))
+                    }
+                    None => Box::new(res),
+                }
+            }
+            _ => Box::new(res),
+        };
+
+        let mut data = FileData::new(
+            data,
+            size,
+            metadata.last_modified.or(date).unwrap_or_else(algo_epoch),
+        );
+        data.last_modified_known = metadata.last_modified.is_some();
+        data.etag = metadata.etag;
+        Ok(data)
+    }
+
+    fn put(&self, uri: &DataUri, body: Body) -> Result<(), Error> {
+        let url = self.url(uri)?;
+        self.client
+            .put(url)
+            .body(body)
+            .send()
+            .with_context(|| format!("request error writing file '{}'", uri))
+            .and_then(process_http_response)
+            .with_context(|| format!("response error writing file '{}'", uri))?;
+        Ok(())
+    }
+
+    fn put_if(&self, uri: &DataUri, condition: &PutCondition, body: Body) -> Result<bool, Error> {
+        let url = self.url(uri)?;
+        let req = match condition {
+            PutCondition::Absent => self.client.put(url).header(IF_NONE_MATCH, "*"),
+            PutCondition::ETag(etag) => self.client.put(url).header(IF_MATCH, etag.as_str()),
+            PutCondition::LastModified(time) => self.client.put(url).header(
+                IF_UNMODIFIED_SINCE,
+                time.format("%a, %d %b %Y %H:%M:%S GMT").to_string(),
+            ),
+        };
+        let res = req
+            .body(body)
+            .send()
+            .with_context(|| format!("request error writing file '{}'", uri))?;
+        if res.status() == StatusCode::PRECONDITION_FAILED {
+            return Ok(false);
+        }
+        process_http_response(res)
+            .with_context(|| format!("response error writing file '{}'", uri))?;
+        Ok(true)
+    }
+
+    fn delete_file(&self, uri: &DataUri) -> Result<(), Error> {
+        let url = self.url(uri)?;
+        self.client
+            .delete(url)
+            .send()
+            .with_context(|| format!("request error deleting file '{}'", uri))
+            .and_then(process_http_response)
+            .with_context(|| format!("response error deleting file '{}'", uri))?;
+        Ok(())
+    }
+
+    fn rename(&self, from: &DataUri, to: &DataUri) -> Result<(), Error> {
+        bail!("the Data API cannot move '{}' to '{}'", from, to)
+    }
+
+    fn supports_rename(&self) -> bool {
+        false
+    }
+
+    fn head(&self, uri: &DataUri) -> Result<Option<ObjectMetadata>, Error> {
+        let url = self.url(uri)?;
+        let res = self
+            .client
+            .head(url)
+            .send()
+            .with_context(|| format!("request error inspecting '{}'", uri))?;
+        if res.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let res = process_http_response(res)
+            .with_context(|| format!("response error inspecting '{}'", uri))?;
+        parse_headers(res.headers()).map(Some)
+    }
+
+    fn list(&self, uri: &DataUri, options: &ListOptions) -> Result<DirectoryPage, Error> {
+        let mut url = self.url(uri)?;
+        if let Some(ref marker) = options.marker {
+            url.query_pairs_mut().append_pair("marker", marker);
+        }
+        if let Some(limit) = options.limit {
+            url.query_pairs_mut()
+                .append_pair("limit", &limit.to_string());
+        }
+        if options.with_acl {
+            url.query_pairs_mut().append_pair("acl", "true");
+        }
+        // The Data API has no filter parameter, so `options.filter` is left to the caller
+
+        let res = self
+            .client
+            .get(url)
+            .send()
+            .with_context(|| format!("request error listing directory '{}'", uri))
+            .and_then(process_http_response)
+            .with_context(|| format!("response error listing directory '{}'", uri))?;
+
+        match res.headers().get(X_DATA_TYPE).map(lossy_header) {
+            Some(ref dt) if dt == "directory" => (),
+            data_type => {
+                let dt = data_type.unwrap_or_else(|| "unknown".to_string());
+                bail!("expected content type '{}', received '{}'", "directory", dt)
+            }
+        }
+
+        res.json::<DirectoryShow>()
+            .map(DirectoryPage::from)
+            .with_context(|| format!("JSON decoding error listing directory '{}'", uri))
+    }
+
+    fn mkdir(&self, uri: &DataUri, acl: Option<&DataAcl>) -> Result<(), Error> {
+        let parent = uri
+            .parent()
+            .ok_or_else(|| err_msg(format!("URI {} does not have a valid parent", uri)))?;
+        let name = uri
+            .file_name()
+            .ok_or_else(|| err_msg(format!("Data URI {} does not have a valid basename", uri)))?;
+        let input_data = FolderItem {
+            name: name.into(),
+            acl: acl.cloned(),
+        };
+
+        self.client
+            .post(self.url(&parent)?)
+            .json(&input_data)
+            .send()
+            .with_context(|| format!("request error creating directory '{}'", uri))
+            .and_then(process_http_response)
+            .with_context(|| format!("response error creating directory '{}'", uri))?;
+        Ok(())
+    }
+
+    fn set_acl(&self, uri: &DataUri, acl: &DataAcl) -> Result<(), Error> {
+        let url = self.url(uri)?;
+        self.client
+            .patch(url)
+            .json(&AclUpdate { acl })
+            .send()
+            .with_context(|| format!("request error updating ACL of '{}'", uri))
+            .and_then(process_http_response)
+            .with_context(|| format!("response error updating ACL of '{}'", uri))?;
+        Ok(())
+    }
+
+    fn delete_dir(&self, uri: &DataUri, force: bool) -> Result<u64, Error> {
+        let mut url = self.url(uri)?;
+        if force {
+            url.query_pairs_mut().append_pair("force", "true");
+        }
+
+        let res = self
+            .client
+            .delete(url)
+            .send()
+            .with_context(|| format!("request error deleting directory '{}'", uri))
+            .and_then(process_http_response)
+            .with_context(|| format!("response error deleting directory '{}'", uri))?;
+
+        res.json::<DeletedResponse>()
+            .map(|res| res.result.deleted)
+            .with_context(|| format!("JSON decoding error deleting directory '{}'", uri))
+    }
+}
+
+/// Page of sorted `folders` and `files`, filtered and limited by `options`
+///
+/// Used by backends that hold the full contents of a directory.
+/// Entries are ordered folders first, and markers name the last entry of the page.
+pub(crate) fn paginate(
+    mut folders: Vec<String>,
+    mut files: Vec<FileEntry>,
+    options: &ListOptions,
+) -> DirectoryPage {
+    if !options.filter.folders() {
+        folders.clear();
+    }
+    if !options.filter.files() {
+        files.clear();
+    }
+    folders.sort();
+    files.sort_by(|a, b| a.name.cmp(&b.name));
+
+    // Markers are `d/<name>` or `f/<name>`, which sort in listing order
+    let after = options.marker.as_deref().unwrap_or("");
+    let mut folders: Vec<String> = folders
+        .into_iter()
+        .filter(|name| format!("d/{}", name).as_str() > after)
+        .collect();
+    let mut files: Vec<FileEntry> = files
+        .into_iter()
+        .filter(|file| format!("f/{}", file.name).as_str() > after)
+        .collect();
+
+    let mut marker = None;
+    if let Some(limit) = options.limit {
+        let limit = limit.max(1) as usize;
+        if folders.len() + files.len() > limit {
+            if folders.len() >= limit {
+                folders.truncate(limit);
+                files.clear();
+                marker = folders.last().map(|name| format!("d/{}", name));
+            } else {
+                files.truncate(limit - folders.len());
+                marker = files.last().map(|file| format!("f/{}", file.name));
+            }
+        }
+    }
+
+    DirectoryPage {
+        acl: None,
+        folders,
+        files,
+        marker,
+    }
+}
+
+/// Whether a directory always exists (i.e. a connector root or its immediate child)
+pub(crate) fn is_implicit_dir(uri: &DataUri) -> bool {
+    uri.segments().len() <= 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::ReadAcl;
+
+    #[test]
+    fn test_acl_serde() {
+        let json = r#"{"acl":{"read":["user://*"]},"folders":[],"marker":null}"#;
+        let show: DirectoryShow = serde_json::from_str(json).unwrap();
+        assert_eq!(show.acl.unwrap(), ReadAcl::Public.into());
+
+        let acl = DataAcl::from(ReadAcl::MyAlgorithms);
+        let update = serde_json::to_string(&AclUpdate { acl: &acl }).unwrap();
+        assert_eq!(update, r#"{"acl":{"read":["algo://.my/*"]}}"#);
+    }
+
+    #[test]
+    fn test_put_condition() {
+        let epoch = crate::data::algo_epoch();
+        let mut current = ObjectMetadata::file(3, epoch);
+        assert!(PutCondition::Absent.matches(None));
+        assert!(!PutCondition::Absent.matches(Some(&current)));
+        assert!(PutCondition::LastModified(epoch).matches(Some(&current)));
+        assert!(!PutCondition::LastModified(epoch).matches(None));
+        assert!(!PutCondition::ETag("\"v1\"".into()).matches(Some(&current)));
+
+        current.etag = Some("\"v1\"".into());
+        assert!(PutCondition::ETag("\"v1\"".into()).matches(Some(&current)));
+        assert!(!PutCondition::ETag("\"v2\"".into()).matches(Some(&current)));
+    }
+
+    fn file_entry(name: &str) -> FileEntry {
+        FileEntry {
+            name: name.into(),
+            size: 0,
+            last_modified: crate::data::algo_epoch(),
+        }
+    }
+
+    #[test]
+    fn test_paginate() {
+        let folders = || vec!["y".to_string(), "x".to_string()];
+        let files = || vec![file_entry("b"), file_entry("a"), file_entry("c")];
+        let mut options = ListOptions {
+            limit: Some(2),
+            ..ListOptions::default()
+        };
+        let mut listed = Vec::new();
+        loop {
+            let page = paginate(folders(), files(), &options);
+            listed.extend(page.folders);
+            listed.extend(page.files.into_iter().map(|file| file.name));
+            match page.marker {
+                Some(marker) => options.marker = Some(marker),
+                None => break,
+            }
+        }
+        assert_eq!(listed, vec!["x", "y", "a", "b", "c"]);
+
+        let options = ListOptions {
+            filter: ListFilter::Files,
+            ..ListOptions::default()
+        };
+        let page = paginate(folders(), files(), &options);
+        assert!(page.folders.is_empty());
+        assert_eq!(page.files.len(), 3);
+        assert!(page.marker.is_none());
+    }
+
+    #[test]
+    fn test_directory_page() {
+        let json = r#"{
+            "folders": [{"name": "sub"}],
+            "files": [{"filename": "a.txt", "size": 3, "last_modified": "2026-01-02T03:04:05.000Z"}],
+            "marker": "abc"
+        }"#;
+        let show: DirectoryShow = serde_json::from_str(json).unwrap();
+        let page = DirectoryPage::from(show);
+        assert!(page.acl.is_none());
+        assert_eq!(page.folders, vec!["sub".to_string()]);
+        assert_eq!(page.files[0].name, "a.txt");
+        assert_eq!(page.files[0].size, 3);
+        assert_eq!(page.marker, Some("abc".to_string()));
+    }
+}
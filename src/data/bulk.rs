@@ -0,0 +1,268 @@
+//! Deleting many data files at once
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use algorithmia::Algorithmia;
+//! use chrono::{Duration, Utc};
+//!
+//! let client = Algorithmia::client("111112222233333444445555566")?;
+//! let summary = client
+//!     .bulk_delete("data://.my/scratch/**/*.tmp")
+//!     .modified_before(Utc::now() - Duration::days(7))
+//!     .concurrency(16)
+//!     .run()?;
+//! println!("deleted {} files ({} bytes)", summary.deleted.len(), summary.bytes);
+//! for (uri, err) in &summary.failed {
+//!     println!("failed to delete {}: {}", uri, err);
+//! }
+//! # Ok::<(), Box<std::error::Error>>(())
+//! ```
+
+use crate::client::HttpClient;
+use crate::data::{DataFileItem, DataItem, DataUri, Glob, HasDataPath};
+use crate::error::Error;
+use chrono::{DateTime, Utc};
+use std::fmt;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+const DEFAULT_CONCURRENCY: usize = 8;
+
+type FileFilter = Box<dyn Fn(&DataFileItem) -> bool + Send + Sync>;
+
+/// Builder for deleting the files matching a glob pattern and filters
+///
+/// Created with [`Algorithmia::bulk_delete`](../struct.Algorithmia.html#method.bulk_delete)
+/// or [`DataDir::bulk_delete`](struct.DataDir.html#method.bulk_delete).
+/// Only files are deleted; directories are left in place, even if they end up empty.
+pub struct BulkDelete {
+    client: HttpClient,
+    /// Directory the pattern is relative to, taken literally, if any
+    root: Option<DataUri>,
+    pattern: String,
+    filters: Vec<FileFilter>,
+    concurrency: usize,
+    dry_run: bool,
+}
+
+/// Result of a `BulkDelete`
+#[derive(Debug, Default)]
+pub struct DeleteSummary {
+    /// Files that were deleted, or would be deleted by a dry run
+    pub deleted: Vec<String>,
+    /// Total size in bytes of the deleted files
+    pub bytes: u64,
+    /// Files that could not be deleted, with the error
+    pub failed: Vec<(String, Error)>,
+}
+
+impl BulkDelete {
+    pub(crate) fn new(client: HttpClient, pattern: &str) -> BulkDelete {
+        BulkDelete {
+            client,
+            root: None,
+            pattern: pattern.to_owned(),
+            filters: Vec::new(),
+            concurrency: DEFAULT_CONCURRENCY,
+            dry_run: false,
+        }
+    }
+
+    /// Delete the files matching `pattern` relative to the directory `root`
+    pub(crate) fn under(client: HttpClient, root: DataUri, pattern: &str) -> BulkDelete {
+        let mut bulk = BulkDelete::new(client, pattern);
+        bulk.root = Some(root);
+        bulk
+    }
+
+    /// Builder method to only delete files for which `predicate` returns true
+    ///
+    /// Multiple filters must all match for a file to be deleted.
+    pub fn filter<F>(&mut self, predicate: F) -> &mut BulkDelete
+    where
+        F: Fn(&DataFileItem) -> bool + Send + Sync + 'static,
+    {
+        self.filters.push(Box::new(predicate));
+        self
+    }
+
+    /// Builder method to only delete files last modified before `time`
+    pub fn modified_before(&mut self, time: DateTime<Utc>) -> &mut BulkDelete {
+        self.filter(move |file| file.last_modified < time)
+    }
+
+    /// Builder method to only delete files of at least `size` bytes
+    pub fn min_size(&mut self, size: u64) -> &mut BulkDelete {
+        self.filter(move |file| file.size >= size)
+    }
+
+    /// Builder method to set the maximum number of files deleted in parallel (default: 8)
+    pub fn concurrency(&mut self, concurrency: usize) -> &mut BulkDelete {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Builder method to only report the files that would be deleted
+    pub fn dry_run(&mut self, dry_run: bool) -> &mut BulkDelete {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Delete the matching files
+    ///
+    /// All matching files are found before any are deleted, so an error listing
+    /// a directory returns an error without deleting anything. Errors deleting
+    /// individual files are collected in the summary. Each file is deleted once,
+    /// even if the pattern matches it more than once.
+    pub fn run(&self) -> Result<DeleteSummary, Error> {
+        let mut files = Vec::new();
+        let glob = match &self.root {
+            Some(root) => Glob::under(self.client.clone(), root, &self.pattern),
+            None => Glob::new(self.client.clone(), &self.pattern),
+        };
+        for item in glob {
+            if let DataItem::File(file) = item? {
+                if self.filters.iter().all(|filter| filter(&file)) {
+                    files.push(file);
+                }
+            }
+        }
+
+        let mut summary = DeleteSummary::default();
+        if self.dry_run {
+            for file in files {
+                summary.bytes += file.size;
+                summary.deleted.push(file.to_data_uri());
+            }
+            summary.deleted.sort();
+            return Ok(summary);
+        }
+
+        let workers = self.concurrency.min(files.len());
+        let queue = Arc::new(Mutex::new(files.into_iter()));
+        let (tx, rx) = mpsc::channel();
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                let tx = tx.clone();
+                let queue = queue.clone();
+                thread::spawn(move || loop {
+                    // Release the lock before deleting
+                    let next = queue
+                        .lock()
+                        .unwrap_or_else(|poisoned| poisoned.into_inner())
+                        .next();
+                    let file = match next {
+                        Some(file) => file,
+                        None => break,
+                    };
+                    let result = file.delete();
+                    if tx.send((file.to_data_uri(), file.size, result)).is_err() {
+                        break;
+                    }
+                })
+            })
+            .collect();
+        drop(tx);
+        for handle in handles {
+            if let Err(panic) = handle.join() {
+                std::panic::resume_unwind(panic);
+            }
+        }
+
+        for (uri, size, result) in rx {
+            match result {
+                Ok(()) => {
+                    summary.bytes += size;
+                    summary.deleted.push(uri);
+                }
+                Err(err) => summary.failed.push((uri, err)),
+            }
+        }
+        summary.deleted.sort();
+        summary.failed.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(summary)
+    }
+}
+
+impl fmt::Debug for BulkDelete {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BulkDelete")
+            .field("pattern", &self.pattern)
+            .field("filters", &self.filters.len())
+            .field("concurrency", &self.concurrency)
+            .field("dry_run", &self.dry_run)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::{DataAcl, HasDataPath, MemoryBackend};
+    use crate::Algorithmia;
+
+    fn client_with_files() -> Algorithmia {
+        let mut client = Algorithmia::client("").unwrap();
+        client.backend(MemoryBackend::new());
+        client
+            .dir(".my/scratch")
+            .create(DataAcl::default())
+            .unwrap();
+        client
+            .dir(".my/scratch/sub")
+            .create(DataAcl::default())
+            .unwrap();
+        client.file(".my/scratch/a.tmp").put("aaa").unwrap();
+        client.file(".my/scratch/b.txt").put("b").unwrap();
+        client.file(".my/scratch/sub/c.tmp").put("c").unwrap();
+        client
+    }
+
+    #[test]
+    fn test_dry_run() {
+        let client = client_with_files();
+        let summary = client
+            .bulk_delete("data://.my/scratch/**/*.tmp")
+            .dry_run(true)
+            .run()
+            .unwrap();
+        assert_eq!(
+            summary.deleted,
+            vec!["data://.my/scratch/a.tmp", "data://.my/scratch/sub/c.tmp"]
+        );
+        assert_eq!(summary.bytes, 4);
+        assert!(client.file(".my/scratch/a.tmp").exists().unwrap());
+    }
+
+    #[test]
+    fn test_delete_with_filters() {
+        let client = client_with_files();
+        let summary = client
+            .bulk_delete("data://.my/scratch/**/*.tmp")
+            .min_size(2)
+            .concurrency(2)
+            .run()
+            .unwrap();
+        assert_eq!(summary.deleted, vec!["data://.my/scratch/a.tmp"]);
+        assert!(summary.failed.is_empty());
+        assert!(!client.file(".my/scratch/a.tmp").exists().unwrap());
+        assert!(client.file(".my/scratch/sub/c.tmp").exists().unwrap());
+
+        let summary = client.dir(".my/scratch").bulk_delete().run().unwrap();
+        assert_eq!(summary.deleted.len(), 2);
+        assert!(client.dir(".my/scratch/sub").exists().unwrap());
+        assert_eq!(client.dir(".my/scratch").list().count(), 1);
+    }
+
+    #[test]
+    fn test_dir_name_is_not_a_pattern() {
+        let client = client_with_files();
+        client.dir(".my/s*").create(DataAcl::default()).unwrap();
+        client.file(".my/s*/d.tmp").put("d").unwrap();
+
+        let summary = client.dir(".my/s*").bulk_delete().run().unwrap();
+        assert_eq!(summary.deleted, vec!["data://.my/s*/d.tmp"]);
+        assert!(client.file(".my/scratch/a.tmp").exists().unwrap());
+    }
+}
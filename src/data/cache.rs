@@ -0,0 +1,362 @@
+//! Local on-disk cache of downloaded Algorithmia Data Files
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use algorithmia::Algorithmia;
+//! use algorithmia::data::DataCache;
+//!
+//! let mut client = Algorithmia::client("111112222233333444445555566")?;
+//! client.cache(DataCache::new("/tmp/algorithmia-cache", 10 * 1024 * 1024 * 1024)?);
+//!
+//! // Downloaded once, then reused for as long as the file is unchanged
+//! let model_path = client.file(".my/models/model.bin").get_cached()?;
+//! # Ok::<(), Box<std::error::Error>>(())
+//! ```
+
+use crate::data::FileMetadata;
+use crate::error::{Error, ResultExt};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+const CONTENT_EXT: &str = "data";
+const ENTRY_EXT: &str = "json";
+const TMP_EXT: &str = "tmp";
+
+/// Distinguishes temporary downloads started concurrently by this process
+static TMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Directory of cached data file content, bounded in size by evicting the least recently used files
+///
+/// Each cached file is stored alongside the size and last modified timestamp
+/// that the Data API reported for it. Before a cached file is reused,
+/// a `HEAD` request checks that it is unchanged, so cached content is never stale.
+///
+/// Configure a cache for all files of a client with
+/// [`Algorithmia::cache`](../struct.Algorithmia.html#method.cache),
+/// then use [`DataFile::get_cached`](struct.DataFile.html#method.get_cached).
+///
+/// Downloads are written to a temporary file and renamed into place,
+/// so processes sharing a cache directory never observe partially downloaded content.
+#[derive(Debug)]
+pub struct DataCache {
+    dir: PathBuf,
+    max_size: u64,
+    lock: Mutex<()>,
+}
+
+/// Metadata of a cached file, stored as JSON next to its content
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    data_uri: String,
+    size: u64,
+    last_modified: Option<DateTime<Utc>>,
+    etag: Option<String>,
+    last_access: DateTime<Utc>,
+}
+
+impl CacheEntry {
+    fn is_current(&self, metadata: &FileMetadata) -> bool {
+        if self.size != metadata.size {
+            return false;
+        }
+        // Without a validator the file may have changed, so it is never considered current
+        match (&metadata.etag, metadata.last_modified) {
+            (Some(current), _) => self.etag.as_ref() == Some(current),
+            (None, Some(current)) => self.last_modified == Some(current),
+            (None, None) => false,
+        }
+    }
+}
+
+impl DataCache {
+    /// Use (and create if necessary) the cache directory `dir`, holding at most `max_size` bytes
+    pub fn new<P: Into<PathBuf>>(dir: P, max_size: u64) -> Result<DataCache, Error> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("error creating cache directory '{}'", dir.display()))?;
+        Ok(DataCache {
+            dir,
+            max_size,
+            lock: Mutex::new(()),
+        })
+    }
+
+    /// The cache directory
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Maximum number of bytes of content held in the cache
+    pub fn max_size(&self) -> u64 {
+        self.max_size
+    }
+
+    /// Number of bytes of content currently held in the cache
+    pub fn size(&self) -> Result<u64, Error> {
+        Ok(self.entries()?.iter().map(|(_, entry)| entry.size).sum())
+    }
+
+    /// Remove all cached content
+    pub fn clear(&self) -> Result<(), Error> {
+        let _guard = self
+            .lock
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        for (key, _) in self.entries()? {
+            self.remove(&key)?;
+        }
+        Ok(())
+    }
+
+    /// Path of the cached content of `data_uri` if it matches `metadata`
+    ///
+    /// Marks the entry as recently used.
+    pub(crate) fn lookup(&self, data_uri: &str, metadata: &FileMetadata) -> Option<PathBuf> {
+        let _guard = self
+            .lock
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let key = cache_key(data_uri);
+        let mut entry = self.read_entry(&key)?;
+        let content_path = self.content_path(&key);
+        if entry.data_uri != data_uri || !entry.is_current(metadata) || !content_path.is_file() {
+            return None;
+        }
+
+        entry.last_access = Utc::now();
+        // Failing to record the access only makes the entry more likely to be evicted
+        let _ = self.write_entry(&key, &entry);
+        Some(content_path)
+    }
+
+    /// Cache the content of `data_uri` written to a temporary path by `download`
+    ///
+    /// Evicts least recently used entries (other than this one) to stay within `max_size`.
+    pub(crate) fn insert<F>(
+        &self,
+        data_uri: &str,
+        metadata: &FileMetadata,
+        download: F,
+    ) -> Result<PathBuf, Error>
+    where
+        F: FnOnce(&Path) -> Result<u64, Error>,
+    {
+        let key = cache_key(data_uri);
+        let tmp_path = self.dir.join(format!(
+            "{}.{}.{}.{}",
+            key,
+            process::id(),
+            TMP_COUNTER.fetch_add(1, Ordering::Relaxed),
+            TMP_EXT
+        ));
+        let _ = fs::remove_file(&tmp_path);
+        let size = match download(&tmp_path) {
+            Ok(size) => size,
+            Err(err) => {
+                let _ = fs::remove_file(&tmp_path);
+                return Err(err);
+            }
+        };
+
+        let _guard = self
+            .lock
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let content_path = self.content_path(&key);
+        fs::rename(&tmp_path, &content_path).with_context(|| {
+            format!(
+                "error moving download into cache '{}'",
+                content_path.display()
+            )
+        })?;
+        let entry = CacheEntry {
+            data_uri: data_uri.to_owned(),
+            size,
+            last_modified: metadata.last_modified,
+            etag: metadata.etag.clone(),
+            last_access: Utc::now(),
+        };
+        self.write_entry(&key, &entry)?;
+        self.evict(&key)?;
+        Ok(content_path)
+    }
+
+    /// Remove least recently used entries until the cache fits within `max_size`
+    fn evict(&self, keep: &str) -> Result<(), Error> {
+        let mut entries = self.entries()?;
+        let mut size: u64 = entries.iter().map(|(_, entry)| entry.size).sum();
+        entries.sort_by_key(|(_, entry)| entry.last_access);
+        for (key, entry) in entries {
+            if size <= self.max_size {
+                break;
+            }
+            if key != keep {
+                self.remove(&key)?;
+                size -= entry.size;
+            }
+        }
+        Ok(())
+    }
+
+    fn entries(&self) -> Result<Vec<(String, CacheEntry)>, Error> {
+        let read_dir = fs::read_dir(&self.dir)
+            .with_context(|| format!("error reading cache directory '{}'", self.dir.display()))?;
+        let mut entries = Vec::new();
+        for dir_entry in read_dir {
+            let path = dir_entry
+                .with_context(|| format!("error reading cache directory '{}'", self.dir.display()))?
+                .path();
+            if path.extension().is_none_or(|ext| ext != ENTRY_EXT) {
+                continue;
+            }
+            let key = match path.file_stem().and_then(|stem| stem.to_str()) {
+                Some(key) => key.to_owned(),
+                None => continue,
+            };
+            if let Some(entry) = self.read_entry(&key) {
+                entries.push((key, entry));
+            }
+        }
+        Ok(entries)
+    }
+
+    fn read_entry(&self, key: &str) -> Option<CacheEntry> {
+        let file = File::open(self.entry_path(key)).ok()?;
+        serde_json::from_reader(io::BufReader::new(file)).ok()
+    }
+
+    fn write_entry(&self, key: &str, entry: &CacheEntry) -> Result<(), Error> {
+        let path = self.entry_path(key);
+        let json = serde_json::to_vec(entry).context("JSON encoding error writing cache entry")?;
+        fs::write(&path, json)
+            .with_context(|| format!("error writing cache entry '{}'", path.display()))
+    }
+
+    fn remove(&self, key: &str) -> Result<(), Error> {
+        for path in &[self.entry_path(key), self.content_path(key)] {
+            match fs::remove_file(path) {
+                Ok(()) => (),
+                Err(ref err) if err.kind() == io::ErrorKind::NotFound => (),
+                Err(err) => {
+                    return Err(err).with_context(|| {
+                        format!("error removing cached file '{}'", path.display())
+                    })
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn content_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.{}", key, CONTENT_EXT))
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.{}", key, ENTRY_EXT))
+    }
+}
+
+/// Stable file name for the cached content of a data URI (64-bit FNV-1a hash)
+///
+/// Entries record their full data URI, so a hash collision is only a cache miss.
+fn cache_key(data_uri: &str) -> String {
+    let hash = data_uri
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::test_support::LocalTempDir;
+    use chrono::TimeZone;
+
+    fn metadata(size: u64, timestamp: i64) -> FileMetadata {
+        FileMetadata {
+            size,
+            last_modified: Some(Utc.timestamp_opt(timestamp, 0).unwrap()),
+            content_type: None,
+            etag: None,
+        }
+    }
+
+    fn insert(cache: &DataCache, data_uri: &str, content: &str, timestamp: i64) -> PathBuf {
+        let metadata = metadata(content.len() as u64, timestamp);
+        cache
+            .insert(data_uri, &metadata, |path| {
+                fs::write(path, content).context("write error")?;
+                Ok(content.len() as u64)
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_lookup_revalidates() {
+        let dir = LocalTempDir::new("cache");
+        let cache = DataCache::new(&*dir, 1024).unwrap();
+        let path = insert(&cache, "data://.my/foo/a.txt", "hello", 100);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "hello");
+
+        assert_eq!(
+            cache.lookup("data://.my/foo/a.txt", &metadata(5, 100)),
+            Some(path)
+        );
+        assert_eq!(
+            cache.lookup("data://.my/foo/a.txt", &metadata(5, 200)),
+            None
+        );
+        assert_eq!(
+            cache.lookup("data://.my/foo/a.txt", &metadata(6, 100)),
+            None
+        );
+        assert_eq!(
+            cache.lookup("data://.my/foo/b.txt", &metadata(5, 100)),
+            None
+        );
+
+        let mut unknown = metadata(5, 100);
+        unknown.last_modified = None;
+        assert_eq!(cache.lookup("data://.my/foo/a.txt", &unknown), None);
+    }
+
+    #[test]
+    fn test_lru_eviction() {
+        let dir = LocalTempDir::new("cache");
+        let cache = DataCache::new(&*dir, 10).unwrap();
+        insert(&cache, "data://.my/foo/a", "aaaa", 1);
+        insert(&cache, "data://.my/foo/b", "bbbb", 1);
+        // Using `a` makes `b` the least recently used
+        assert!(cache.lookup("data://.my/foo/a", &metadata(4, 1)).is_some());
+        insert(&cache, "data://.my/foo/c", "cccc", 1);
+
+        assert_eq!(cache.size().unwrap(), 8);
+        assert!(cache.lookup("data://.my/foo/a", &metadata(4, 1)).is_some());
+        assert!(cache.lookup("data://.my/foo/b", &metadata(4, 1)).is_none());
+        assert!(cache.lookup("data://.my/foo/c", &metadata(4, 1)).is_some());
+
+        cache.clear().unwrap();
+        assert_eq!(cache.size().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_failed_download() {
+        let dir = LocalTempDir::new("cache");
+        let cache = DataCache::new(&*dir, 1024).unwrap();
+        let result = cache.insert("data://.my/foo/a", &metadata(4, 1), |path| {
+            fs::write(path, "part").context("write error")?;
+            bail!("connection reset")
+        });
+        assert!(result.is_err());
+        assert_eq!(fs::read_dir(cache.dir()).unwrap().count(), 0);
+    }
+}
@@ -0,0 +1,330 @@
+//! Transparent compression of Algorithmia Data Files
+//!
+//! Requires the `gzip` or `zstd` feature. The codec is not stored with the content, so callers
+//! must name compressed files with the extension of their codec (`.gz` or `.zst`):
+//! it is how `DataFile::get_decompressed` and other clients detect how to decode them.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # #[cfg(feature = "gzip")]
+//! # fn main() -> Result<(), Box<std::error::Error>> {
+//! use algorithmia::Algorithmia;
+//! use algorithmia::data::Compression;
+//! use std::fs::File;
+//!
+//! let client = Algorithmia::client("111112222233333444445555566")?;
+//!
+//! // Explicitly compress a stream into `report.csv.gz`
+//! let report = File::open("/path/to/report.csv")?;
+//! client.file(".my/my_dir/report.csv.gz").put_compressed(report, Compression::Gzip)?;
+//! let text = client.file(".my/my_dir/report.csv.gz").get_decompressed()?.into_string()?;
+//!
+//! // Or transparently compress everything written to and read from `results.json.gz`
+//! let mut results = client.file(".my/my_dir/results.json.gz");
+//! results.compression(Compression::Gzip);
+//! results.put_json(&vec![1, 2, 3])?;
+//! let decoded: Vec<u32> = results.get_json()?;
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "gzip"))]
+//! # fn main() {}
+//! ```
+
+use crate::data::{DataFile, DataUri, Direction, FileData, HasDataPath, ProgressReader};
+use crate::error::{Error, ResultExt};
+use crate::Body;
+use std::io::{self, Read};
+
+/// Compression codec applied to the content of a data file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    /// gzip compression, tagged with the `.gz` extension
+    #[cfg(feature = "gzip")]
+    Gzip,
+    /// Zstandard compression, tagged with the `.zst` extension
+    #[cfg(feature = "zstd")]
+    Zstd,
+}
+
+impl Compression {
+    /// File extension (without the leading dot) that tags content with this codec
+    pub fn extension(self) -> &'static str {
+        match self {
+            #[cfg(feature = "gzip")]
+            Compression::Gzip => "gz",
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => "zst",
+        }
+    }
+
+    /// Codec tagged by a file extension, if it is an enabled codec
+    pub fn from_extension(extension: &str) -> Option<Compression> {
+        match extension {
+            #[cfg(feature = "gzip")]
+            "gz" => Some(Compression::Gzip),
+            #[cfg(feature = "zstd")]
+            "zst" => Some(Compression::Zstd),
+            _ => None,
+        }
+    }
+
+    /// Codec tagged by the extension of a data URI
+    pub fn for_uri(uri: &DataUri) -> Option<Compression> {
+        uri.extension().and_then(Compression::from_extension)
+    }
+
+    /// Wrap a reader of raw content with a reader of its compressed content
+    pub(crate) fn encoder<R>(self, reader: R) -> Result<Box<dyn Read + Send>, Error>
+    where
+        R: Read + Send + 'static,
+    {
+        match self {
+            #[cfg(feature = "gzip")]
+            Compression::Gzip => Ok(Box::new(flate2::read::GzEncoder::new(
+                reader,
+                Default::default(),
+            ))),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => {
+                let encoder = zstd::stream::read::Encoder::new(reader, 0)
+                    .context("zstd compression error")?;
+                Ok(Box::new(encoder))
+            }
+        }
+    }
+
+    /// Wrap a reader of compressed content with a reader of its raw content
+    pub(crate) fn decoder<R>(self, reader: R) -> Result<Box<dyn Read>, Error>
+    where
+        R: Read + 'static,
+    {
+        match self {
+            #[cfg(feature = "gzip")]
+            Compression::Gzip => Ok(Box::new(flate2::read::MultiGzDecoder::new(reader))),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => {
+                let decoder = zstd::Decoder::new(reader).context("zstd decompression error")?;
+                Ok(Box::new(decoder))
+            }
+        }
+    }
+}
+
+impl DataFile {
+    /// Builder method to transparently compress the content of this file
+    ///
+    /// `put`, `put_file`, `put_sized`, `put_json`, `writer` compress the content they upload,
+    /// and `get`, `get_json` decompress the content they download.
+    /// `get_range`, `open_seekable`, and `download_to_path` still operate on the stored (compressed) bytes.
+    ///
+    /// The codec is not recorded with the content and the path of the file is not changed:
+    /// callers must name the file with the extension of the codec (see `Compression::extension`),
+    /// or `get_decompressed` and other clients cannot tell that it is compressed.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[cfg(feature = "gzip")]
+    /// # fn main() -> Result<(), Box<std::error::Error>> {
+    /// # use algorithmia::Algorithmia;
+    /// # use algorithmia::data::Compression;
+    /// let client = Algorithmia::client("111112222233333444445555566")?;
+    /// let mut log = client.file(".my/my_dir/log.txt.gz");
+    /// log.compression(Compression::Gzip);
+    /// log.put("highly compressible text")?;
+    /// let text = log.get()?.into_string()?;
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(feature = "gzip"))]
+    /// # fn main() {}
+    /// ```
+    pub fn compression(&mut self, compression: Compression) -> &mut DataFile {
+        self.compression = Some(compression);
+        self
+    }
+
+    /// Compress the content of a reader while uploading it
+    ///
+    /// Name the file with the extension of the codec (see `Compression::extension`)
+    /// so that `get_decompressed` can detect how to decode it.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[cfg(feature = "gzip")]
+    /// # fn main() -> Result<(), Box<std::error::Error>> {
+    /// # use algorithmia::Algorithmia;
+    /// # use algorithmia::data::Compression;
+    /// # use std::fs::File;
+    /// let client = Algorithmia::client("111112222233333444445555566")?;
+    /// let report = File::open("/path/to/report.csv")?;
+    /// client.file(".my/my_dir/report.csv.gz").put_compressed(report, Compression::Gzip)?;
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(feature = "gzip"))]
+    /// # fn main() {}
+    /// ```
+    pub fn put_compressed<R>(&self, reader: R, compression: Compression) -> Result<(), Error>
+    where
+        R: Read + Send + 'static,
+    {
+        let reader = compression.encoder(reader)?;
+        match self.tracker(Direction::Upload, None, 0) {
+            Some(tracker) => self.put_raw(Body::new(ProgressReader::new(reader, tracker))),
+            None => self.put_raw(Body::new(reader)),
+        }
+    }
+
+    /// Get a file from the Algorithmia Data API and decompress its content
+    ///
+    /// The codec is the one configured with `compression`, or else is detected from
+    /// the extension of the file name. The `size` of the returned `FileData` is the stored (compressed) size.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use algorithmia::Algorithmia;
+    /// let client = Algorithmia::client("111112222233333444445555566")?;
+    /// let text = client.file(".my/my_dir/report.csv.gz").get_decompressed()?.into_string()?;
+    /// # Ok::<_, Box<std::error::Error>>(())
+    /// ```
+    pub fn get_decompressed(&self) -> Result<FileData, Error> {
+        let compression = match self
+            .compression
+            .or_else(|| Compression::for_uri(&self.data_uri()))
+        {
+            Some(compression) => compression,
+            None => bail!(
+                "cannot detect the compression of file '{}' from its extension",
+                self.to_data_uri()
+            ),
+        };
+        let data = self
+            .request_file(0, None)
+            .map(|data| self.track_download(data))?;
+        decompress(data, compression)
+    }
+
+    /// Compress an in-memory body, if compression is configured
+    ///
+    /// Streaming bodies cannot be compressed, so they fail if compression is configured.
+    pub(crate) fn encode_body(&self, body: Body) -> Result<Body, Error> {
+        let compression = match self.compression {
+            Some(compression) => compression,
+            None => return Ok(body),
+        };
+        let bytes = match body.as_bytes() {
+            Some(bytes) => bytes.to_vec(),
+            None => bail!(
+                "cannot transparently compress a streaming body, use 'put_file', 'put_sized', 'put_compressed' or 'writer'"
+            ),
+        };
+        let mut compressed = Vec::new();
+        compression
+            .encoder(io::Cursor::new(bytes))?
+            .read_to_end(&mut compressed)
+            .with_context(|| format!("error compressing file '{}'", self.to_data_uri()))?;
+        Ok(compressed.into())
+    }
+
+    /// Body uploading the content of a reader, compressed while it is read if configured
+    ///
+    /// `len` is the size of the uncompressed content, if known.
+    pub(crate) fn encode_reader<R>(&self, reader: R, len: Option<u64>) -> Result<Body, Error>
+    where
+        R: Read + Send + 'static,
+    {
+        match (self.compression, len) {
+            (Some(compression), _) => Ok(Body::new(compression.encoder(reader)?)),
+            (None, Some(len)) => Ok(Body::sized(reader, len)),
+            (None, None) => Ok(Body::new(reader)),
+        }
+    }
+
+    /// Decompress downloaded content, if compression is configured
+    pub(crate) fn decode_data(&self, data: FileData) -> Result<FileData, Error> {
+        match self.compression {
+            Some(compression) => decompress(data, compression),
+            None => Ok(data),
+        }
+    }
+}
+
+fn decompress(data: FileData, compression: Compression) -> Result<FileData, Error> {
+    data.try_map_reader(|reader| compression.decoder(reader))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(compression: Compression) {
+        let text = "compressible text ".repeat(1000);
+        let mut compressed = Vec::new();
+        compression
+            .encoder(io::Cursor::new(text.clone()))
+            .unwrap()
+            .read_to_end(&mut compressed)
+            .unwrap();
+        assert!(compressed.len() < text.len());
+
+        let mut decompressed = String::new();
+        compression
+            .decoder(io::Cursor::new(compressed))
+            .unwrap()
+            .read_to_string(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, text);
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_gzip() {
+        round_trip(Compression::Gzip);
+        let uri = DataUri::from("data://.my/foo/report.csv.gz");
+        assert_eq!(Compression::for_uri(&uri), Some(Compression::Gzip));
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn test_zstd() {
+        round_trip(Compression::Zstd);
+        let uri = DataUri::from("data://.my/foo/report.csv.zst");
+        assert_eq!(Compression::for_uri(&uri), Some(Compression::Zstd));
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_transparent_compression() {
+        use crate::data::MemoryBackend;
+        use crate::Algorithmia;
+
+        let mut client = Algorithmia::client("").unwrap();
+        client.backend(MemoryBackend::new());
+        let mut file = client.file(".my/log.txt.gz");
+        file.compression(Compression::Gzip);
+        assert_eq!(file.to_data_uri(), "data://.my/log.txt.gz");
+
+        file.put("compressible text").unwrap();
+        assert_eq!(
+            file.get().unwrap().into_string().unwrap(),
+            "compressible text"
+        );
+        let stored = client
+            .file(".my/log.txt.gz")
+            .get()
+            .unwrap()
+            .into_bytes()
+            .unwrap();
+        assert_ne!(stored, b"compressible text");
+
+        file.put_reader(io::Cursor::new("streamed text")).unwrap();
+        assert_eq!(
+            file.get_decompressed().unwrap().into_string().unwrap(),
+            "streamed text"
+        );
+    }
+
+    #[test]
+    fn test_unknown_extension() {
+        assert_eq!(Compression::from_extension("csv"), None);
+    }
+}
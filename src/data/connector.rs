@@ -0,0 +1,178 @@
+//! External data connectors (e.g. `s3://`, `dropbox://`) reachable through the Data API
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use algorithmia::Algorithmia;
+//! use algorithmia::data::{DataConnector, HasDataPath};
+//!
+//! let client = Algorithmia::client("111112222233333444445555566")?;
+//! for connector in client.connectors()? {
+//!     println!("{} ({})", connector.name, connector.connector);
+//! }
+//!
+//! let file = client.file("s3://my-bucket/models/model.bin");
+//! assert_eq!(file.connector(), DataConnector::S3);
+//! assert_eq!(file.bucket().as_deref(), Some("my-bucket"));
+//! # Ok::<(), Box<std::error::Error>>(())
+//! ```
+
+use crate::client::HttpClient;
+use crate::error::{process_http_response, Error, ResultExt};
+use serde::{Deserialize, Deserializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// Storage backend that a data URI refers to, identified by its scheme
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DataConnector {
+    /// Algorithmia hosted data (`data://`)
+    Data,
+    /// Amazon S3 (`s3://<bucket>/...`)
+    S3,
+    /// Dropbox (`dropbox://`)
+    Dropbox,
+    /// Azure Blob Storage (`azureblob://<container>/...`)
+    AzureBlob,
+    /// Google Cloud Storage (`gs://<bucket>/...`)
+    GoogleCloudStorage,
+    /// Any other connector scheme
+    Other(String),
+}
+
+impl DataConnector {
+    /// The URI scheme of this connector
+    pub fn scheme(&self) -> &str {
+        match self {
+            DataConnector::Data => "data",
+            DataConnector::S3 => "s3",
+            DataConnector::Dropbox => "dropbox",
+            DataConnector::AzureBlob => "azureblob",
+            DataConnector::GoogleCloudStorage => "gs",
+            DataConnector::Other(scheme) => scheme,
+        }
+    }
+
+    /// Whether the first path segment names a bucket (or container)
+    pub fn has_buckets(&self) -> bool {
+        matches!(
+            self,
+            DataConnector::S3 | DataConnector::AzureBlob | DataConnector::GoogleCloudStorage
+        )
+    }
+
+    /// Whether directories on this connector have ACLs managed by Algorithmia
+    pub fn supports_acl(&self) -> bool {
+        *self == DataConnector::Data
+    }
+}
+
+impl<'a> From<&'a str> for DataConnector {
+    fn from(scheme: &'a str) -> Self {
+        match scheme {
+            "data" => DataConnector::Data,
+            "s3" => DataConnector::S3,
+            "dropbox" => DataConnector::Dropbox,
+            "azureblob" => DataConnector::AzureBlob,
+            "gs" => DataConnector::GoogleCloudStorage,
+            other => DataConnector::Other(other.to_string()),
+        }
+    }
+}
+
+impl FromStr for DataConnector {
+    type Err = Error;
+    fn from_str(scheme: &str) -> Result<Self, Self::Err> {
+        let scheme = scheme.trim_end_matches("://");
+        if scheme.is_empty() || !scheme.chars().all(|c| c.is_ascii_alphanumeric()) {
+            bail!("invalid data connector scheme '{}'", scheme);
+        }
+        Ok(DataConnector::from(scheme))
+    }
+}
+
+impl fmt::Display for DataConnector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}://", self.scheme())
+    }
+}
+
+impl<'de> Deserialize<'de> for DataConnector {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let scheme = String::deserialize(deserializer)?;
+        Ok(DataConnector::from(scheme.as_str()))
+    }
+}
+
+/// A data connector configured for the authenticated account
+#[derive(Debug, Clone, Deserialize)]
+#[non_exhaustive]
+pub struct ConnectorInfo {
+    /// Name given to the connector
+    #[serde(default)]
+    pub name: String,
+    /// Type of the connector
+    #[serde(rename = "type")]
+    pub connector: DataConnector,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ConnectorList {
+    Results { results: Vec<ConnectorInfo> },
+    List(Vec<ConnectorInfo>),
+}
+
+pub(crate) fn list_connectors(client: &HttpClient) -> Result<Vec<ConnectorInfo>, Error> {
+    let url = client
+        .base_url
+        .join(super::DATA_BASE_PATH)
+        .context("Failed to construct URL for listing data connectors")?;
+    let res = client
+        .get(url)
+        .send()
+        .context("request error listing data connectors")
+        .and_then(process_http_response)
+        .context("response error listing data connectors")?;
+
+    match res
+        .json::<ConnectorList>()
+        .context("JSON decoding error listing data connectors")?
+    {
+        ConnectorList::Results { results } => Ok(results),
+        ConnectorList::List(list) => Ok(list),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connector_schemes() {
+        for scheme in &["data", "s3", "dropbox", "azureblob", "gs", "ftp"] {
+            assert_eq!(DataConnector::from(*scheme).scheme(), *scheme);
+        }
+        assert_eq!(
+            DataConnector::from("ftp"),
+            DataConnector::Other("ftp".into())
+        );
+        assert_eq!(
+            "gs://".parse::<DataConnector>().unwrap(),
+            DataConnector::GoogleCloudStorage
+        );
+        assert!("s3:/".parse::<DataConnector>().is_err());
+    }
+
+    #[test]
+    fn test_decode_connector_list() {
+        let json = r#"{"results":[{"name":"prod","type":"s3"},{"type":"dropbox"}]}"#;
+        let list = match serde_json::from_str::<ConnectorList>(json).unwrap() {
+            ConnectorList::Results { results } => results,
+            ConnectorList::List(list) => list,
+        };
+        assert_eq!(list[0].name, "prod");
+        assert_eq!(list[0].connector, DataConnector::S3);
+        assert_eq!(list[1].connector, DataConnector::Dropbox);
+    }
+}
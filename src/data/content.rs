@@ -0,0 +1,297 @@
+//! Content-addressed storage of immutable data
+//!
+//! Content is stored under its SHA-256 digest, so identical content is only stored once,
+//! and is verified against its digest when read. Requires the `content-store` feature.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use algorithmia::Algorithmia;
+//! use algorithmia::data::ContentStore;
+//! use std::fs::File;
+//!
+//! let client = Algorithmia::client("111112222233333444445555566")?;
+//! let artifacts = ContentStore::new(client.dir(".my/artifacts"));
+//!
+//! let digest = artifacts.put_reader(File::open("/path/to/model.bin")?)?;
+//! println!("stored model as {}", digest);
+//!
+//! let model = artifacts.get(&digest)?.into_bytes()?;
+//! # Ok::<(), Box<std::error::Error>>(())
+//! ```
+
+use crate::data::temp::create_spool_file;
+use crate::data::{DataAcl, DataDir, DataFile, FileData, HasDataPath};
+use crate::error::{err_msg, Error, ResultExt};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
+use std::convert::TryFrom;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::str::FromStr;
+
+/// SHA-256 digest identifying content in a `ContentStore`
+///
+/// Displayed, parsed, and serialized as 64 lowercase hexadecimal characters.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Digest([u8; 32]);
+
+impl Digest {
+    /// Digest of `content`
+    pub fn of(content: &[u8]) -> Digest {
+        Digest(Sha256::digest(content).into())
+    }
+
+    /// Bytes of the digest
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Hexadecimal representation of the digest
+    pub fn to_hex(&self) -> String {
+        self.0.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+}
+
+impl fmt::Display for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+impl fmt::Debug for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Digest({})", self.to_hex())
+    }
+}
+
+impl FromStr for Digest {
+    type Err = Error;
+    fn from_str(hex: &str) -> Result<Digest, Error> {
+        let invalid = || err_msg(format!("invalid SHA-256 digest '{}'", hex));
+        if hex.len() != 64 || !hex.is_ascii() {
+            return Err(invalid());
+        }
+        let mut bytes = [0; 32];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).map_err(|_| invalid())?;
+        }
+        Ok(Digest(bytes))
+    }
+}
+
+impl TryFrom<String> for Digest {
+    type Error = Error;
+    fn try_from(hex: String) -> Result<Digest, Error> {
+        hex.parse()
+    }
+}
+
+impl From<Digest> for String {
+    fn from(digest: Digest) -> String {
+        digest.to_hex()
+    }
+}
+
+/// Store of immutable content under its SHA-256 digest in a `DataDir`
+///
+/// Content with digest `abcdef...` is stored in the file `ab/cd/abcdef...` of the directory,
+/// so no directory holds too many files. Subdirectories are created as needed,
+/// but the directory itself must exist.
+#[derive(Clone)]
+pub struct ContentStore {
+    dir: DataDir,
+}
+
+impl ContentStore {
+    /// Store content in the files of `dir`
+    pub fn new(dir: DataDir) -> ContentStore {
+        ContentStore { dir }
+    }
+
+    /// The directory content is stored in
+    pub fn dir(&self) -> &DataDir {
+        &self.dir
+    }
+
+    /// The file that stores the content with `digest`
+    pub fn file(&self, digest: &Digest) -> DataFile {
+        let hex = digest.to_hex();
+        self.dir
+            .child(&format!("{}/{}/{}", &hex[..2], &hex[2..4], hex))
+    }
+
+    /// Whether content with `digest` is stored
+    pub fn exists(&self, digest: &Digest) -> Result<bool, Error> {
+        self.file(digest).exists()
+    }
+
+    /// Store content, returning its digest
+    ///
+    /// Content that is already stored is not uploaded again.
+    pub fn put<B: AsRef<[u8]>>(&self, content: B) -> Result<Digest, Error> {
+        let content = content.as_ref();
+        let digest = Digest::of(content);
+        if !self.exists(&digest)? {
+            self.create_shards(&digest)?;
+            self.file(&digest).put(content.to_vec())?;
+        }
+        Ok(digest)
+    }
+
+    /// Store the content of a reader, returning its digest
+    ///
+    /// The content is hashed while it is written to a temporary local file,
+    /// which is uploaded once the digest is known, unless the content is already stored.
+    pub fn put_reader<R: Read>(&self, reader: R) -> Result<Digest, Error> {
+        let (spool_path, spool) = create_spool_file("content")
+            .context("error creating temporary file to spool content")?;
+        let result = hash_to(reader, spool)
+            .with_context(|| format!("error spooling content to '{}'", spool_path.display()))
+            .and_then(|(digest, len)| {
+                if !self.exists(&digest)? {
+                    self.create_shards(&digest)?;
+                    let spool = File::open(&spool_path).with_context(|| {
+                        format!("error reading spooled content '{}'", spool_path.display())
+                    })?;
+                    self.file(&digest).put_sized(spool, len)?;
+                }
+                Ok(digest)
+            });
+        let _ = fs::remove_file(&spool_path);
+        result
+    }
+
+    /// Get content by its digest
+    ///
+    /// The content is hashed while it is read, and reading fails with an
+    /// `InvalidData` error at the end of the content if it does not match the digest.
+    pub fn get(&self, digest: &Digest) -> Result<FileData, Error> {
+        let data = self.file(digest).get()?;
+        let digest = *digest;
+        Ok(data.map_reader(move |inner| {
+            Box::new(VerifyingReader {
+                inner,
+                hasher: Sha256::new(),
+                digest,
+                verified: false,
+            })
+        }))
+    }
+
+    fn create_shards(&self, digest: &Digest) -> Result<(), Error> {
+        let hex = digest.to_hex();
+        let shards = [
+            hex[..2].to_string(),
+            format!("{}/{}", &hex[..2], &hex[2..4]),
+        ];
+        for shard in &shards {
+            let dir: DataDir = self.dir.child(shard);
+            if dir.exists()? {
+                continue;
+            }
+            if let Err(err) = dir.create(DataAcl::default()) {
+                // Another writer may have created it concurrently
+                if !dir.exists()? {
+                    return Err(err);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Copy `reader` to `writer`, returning the digest and length of the content
+fn hash_to<R: Read, W: Write>(mut reader: R, mut writer: W) -> io::Result<(Digest, u64)> {
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0; 64 * 1024];
+    let mut len = 0;
+    loop {
+        let n = reader.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        writer.write_all(&buf[..n])?;
+        len += n as u64;
+    }
+    Ok((Digest(hasher.finalize().into()), len))
+}
+
+/// Reader that checks the digest of its content once it has all been read
+struct VerifyingReader {
+    inner: Box<dyn Read>,
+    hasher: Sha256,
+    digest: Digest,
+    verified: bool,
+}
+
+impl Read for VerifyingReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        if n > 0 {
+            self.hasher.update(&buf[..n]);
+        } else if !buf.is_empty() && !self.verified {
+            let actual = Digest(self.hasher.clone().finalize().into());
+            if actual != self.digest {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "content with digest {} does not match the expected digest {}",
+                        actual, self.digest
+                    ),
+                ));
+            }
+            self.verified = true;
+        }
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::MemoryBackend;
+    use crate::Algorithmia;
+    use std::io::Cursor;
+
+    fn memory_store() -> (Algorithmia, ContentStore) {
+        let mut client = Algorithmia::client("").unwrap();
+        client.backend(MemoryBackend::new());
+        client.dir(".my/cas").create(DataAcl::default()).unwrap();
+        let store = ContentStore::new(client.dir(".my/cas"));
+        (client, store)
+    }
+
+    #[test]
+    fn test_digest() {
+        let digest = Digest::of(b"hello");
+        assert_eq!(
+            digest.to_hex(),
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+        assert_eq!(digest.to_hex().parse::<Digest>().unwrap(), digest);
+        assert!("2cf24d".parse::<Digest>().is_err());
+        let json = serde_json::to_string(&digest).unwrap();
+        assert_eq!(serde_json::from_str::<Digest>(&json).unwrap(), digest);
+    }
+
+    #[test]
+    fn test_put_and_get() {
+        let (client, store) = memory_store();
+        let digest = store.put("hello").unwrap();
+        assert_eq!(
+            store.file(&digest).to_data_uri(),
+            format!("data://.my/cas/2c/f2/{}", digest)
+        );
+        assert_eq!(store.put_reader(Cursor::new("hello")).unwrap(), digest);
+        assert_eq!(client.dir(".my/cas/2c/f2").list().count(), 1);
+        assert_eq!(store.get(&digest).unwrap().into_string().unwrap(), "hello");
+
+        // Tampered content fails verification
+        store.file(&digest).put("tampered").unwrap();
+        assert!(store.get(&digest).unwrap().into_string().is_err());
+    }
+}
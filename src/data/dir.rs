@@ -14,66 +14,53 @@
 //! # Ok::<(), Box<std::error::Error>>(())
 //! ```
 
-use super::parse_data_uri;
-use crate::client::header::{lossy_header, X_DATA_TYPE};
 use crate::client::HttpClient;
-use crate::data::{DataDirItem, DataFile, DataFileItem, DataItem, HasDataPath};
-use crate::error::{err_msg, process_http_response, Error, ResultExt};
+use crate::data::{
+    BulkDelete, DataDirItem, DataFile, DataFileItem, DataItem, DataUri, FileEntry, Glob,
+    HasDataPath, ListFilter, ListOptions, ProgressHandler, TempDataDir, UsageBuilder, Watcher,
+};
+use crate::error::{err_msg, Error};
 
-use std::fs::File;
+use std::marker::PhantomData;
 use std::path::Path;
+use std::sync::Arc;
 use std::vec::IntoIter;
 
-use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 /// Algorithmia Data Directory
+#[derive(Clone)]
 pub struct DataDir {
     path: String,
     client: HttpClient,
-}
-
-#[derive(Debug, Deserialize)]
-struct DeletedResponse {
-    result: DirectoryDeleted,
+    progress: Option<Arc<dyn ProgressHandler>>,
 }
 
 /// Response when deleting a file form the Data API
-#[derive(Debug, Deserialize)]
+#[derive(Debug)]
 pub struct DirectoryDeleted {
     /// Number of files that were deleted
     ///
     /// Note: some backing stores may indicate deletion succeeds for non-existing files
     pub deleted: u64,
     // Placeholder for API stability if additional fields are added later
-    #[serde(skip_deserializing)]
     _dummy: (),
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-struct FolderItem {
-    pub name: String,
-    pub acl: Option<DataAcl>,
-}
-
-#[derive(Debug, Deserialize)]
-struct FileItem {
-    pub filename: String,
-    pub size: u64,
-    pub last_modified: DateTime<Utc>,
-}
-
 /// ACL that indicates permissions for a `DataDir`
-/// See also: [`ReadAcl`](enum.ReadAcl.html) enum to construct a `DataACL`
-#[derive(Debug, Deserialize, Serialize)]
+/// See also: [`ReadAcl`](enum.ReadAcl.html) enum to construct a `DataACL`,
+/// or [`DataAcl::builder`](struct.DataAcl.html#method.builder) to grant access to specific principals
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct DataAcl {
     /// Read ACL
     pub read: Vec<String>,
     // Placeholder for stability with API additions
+    #[serde(skip)]
     _dummy: (),
 }
 
 /// Read access control values
+#[derive(Debug, Clone, PartialEq)]
 pub enum ReadAcl {
     /// Readable only by owner
     Private,
@@ -81,6 +68,8 @@ pub enum ReadAcl {
     MyAlgorithms,
     /// Readable by any user
     Public,
+    /// ACL that doesn't match any of the presets, as raw read ACL strings
+    Custom(Vec<String>),
 
     /// Non-exhaustive for API stability if ACL types are added
     #[doc(hidden)]
@@ -108,119 +97,264 @@ impl From<ReadAcl> for DataAcl {
                 read: vec!["user://*".into()],
                 _dummy: (),
             },
+            ReadAcl::Custom(read) => DataAcl { read, _dummy: () },
         }
     }
 }
 
-/// Response when querying an existing Directory
-#[derive(Debug, Deserialize)]
-struct DirectoryShow {
-    pub acl: Option<DataAcl>,
-    pub folders: Option<Vec<FolderItem>>,
-    pub files: Option<Vec<FileItem>>,
-    pub marker: Option<String>,
+impl From<DataAcl> for ReadAcl {
+    fn from(acl: DataAcl) -> Self {
+        match acl.read.as_slice() {
+            [] => ReadAcl::Private,
+            [read] if read == "algo://.my/*" => ReadAcl::MyAlgorithms,
+            [read] if read == "user://*" => ReadAcl::Public,
+            _ => ReadAcl::Custom(acl.read),
+        }
+    }
 }
 
 /// Iterator over the listing of a `DataDir`
+///
+/// Borrows the `DataDir`; see [`Listing`](struct.Listing.html) for an owned listing
+/// that can be moved across threads, paged, filtered, and resumed.
 pub struct DirectoryListing<'a> {
     /// ACL indicates permissions for this `DataDir`
+    ///
+    /// Populated once the first page of the listing has been fetched
     pub acl: Option<DataAcl>,
-    dir: &'a DataDir,
-    folders: IntoIter<FolderItem>,
-    files: IntoIter<FileItem>,
-    marker: Option<String>,
-    query_count: u32,
+    listing: Listing,
+    _dir: PhantomData<&'a DataDir>,
 }
 
-impl<'a> DirectoryListing<'a> {
-    fn new(dir: &'a DataDir) -> DirectoryListing<'a> {
-        DirectoryListing {
+impl<'a> Iterator for DirectoryListing<'a> {
+    type Item = Result<DataItem, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.listing.next();
+        if self.acl.is_none() {
+            self.acl = self.listing.acl.clone();
+        }
+        item
+    }
+}
+
+/// Owned iterator over the listing of a `DataDir`
+///
+/// Pages are fetched as the listing is iterated. Use `checkpoint` to record the position
+/// of a listing, and `resume` to continue a new listing of the same directory from it.
+///
+/// # Examples
+/// ```no_run
+/// # use algorithmia::Algorithmia;
+/// # use algorithmia::data::{DataItem, HasDataPath};
+/// let client = Algorithmia::client("111112222233333444445555566")?;
+/// let mut listing = client.dir(".my/my_dir").listing();
+/// listing.page_size(100).files_only();
+///
+/// let handle = std::thread::spawn(move || {
+///     for entry in listing {
+///         if let DataItem::File(f) = entry? {
+///             println!("{}: {} bytes", f.to_data_uri(), f.size);
+///         }
+///     }
+///     Ok::<(), algorithmia::error::Error>(())
+/// });
+/// handle.join().unwrap()?;
+/// # Ok::<(), Box<std::error::Error>>(())
+/// ```
+pub struct Listing {
+    dir: DataDir,
+    acl: Option<DataAcl>,
+    page_size: Option<u32>,
+    filter: ListFilter,
+    folders: IntoIter<String>,
+    files: IntoIter<FileEntry>,
+    // Marker the current page was fetched with
+    page_marker: Option<String>,
+    next_marker: Option<String>,
+    // Entries of the current page already returned (or to skip when resuming)
+    offset: usize,
+    started: bool,
+    done: bool,
+}
+
+/// Position within a `Listing`, to resume it later
+///
+/// Only valid for listings of the same directory with the same page size and filter.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ListCheckpoint {
+    /// Marker of the page to resume from, or `None` for the first page
+    pub marker: Option<String>,
+    /// Number of entries of that page already returned
+    pub offset: usize,
+}
+
+impl Listing {
+    fn new(dir: DataDir) -> Listing {
+        Listing {
+            dir,
             acl: None,
-            dir: dir,
+            page_size: None,
+            filter: ListFilter::All,
             folders: Vec::new().into_iter(),
             files: Vec::new().into_iter(),
-            marker: None,
-            query_count: 0,
+            page_marker: None,
+            next_marker: None,
+            offset: 0,
+            started: false,
+            done: false,
+        }
+    }
+
+    /// Builder method to request pages of at most `size` entries
+    ///
+    /// By default, the storage backend chooses the page size.
+    pub fn page_size(&mut self, size: u32) -> &mut Listing {
+        self.page_size = Some(size);
+        self
+    }
+
+    /// Builder method to list only files
+    ///
+    /// Entries are filtered on the client, so pages from the Data API still count
+    /// directories toward the page size.
+    pub fn files_only(&mut self) -> &mut Listing {
+        self.filter = ListFilter::Files;
+        self
+    }
+
+    /// Builder method to list only directories
+    ///
+    /// Entries are filtered on the client, so pages from the Data API still count
+    /// files toward the page size.
+    pub fn folders_only(&mut self) -> &mut Listing {
+        self.filter = ListFilter::Folders;
+        self
+    }
+
+    /// Builder method to continue listing from a checkpoint
+    ///
+    /// Restarts the listing if it has already been iterated.
+    pub fn resume(&mut self, checkpoint: ListCheckpoint) -> &mut Listing {
+        self.folders = Vec::new().into_iter();
+        self.files = Vec::new().into_iter();
+        self.page_marker = checkpoint.marker;
+        self.next_marker = None;
+        self.offset = checkpoint.offset;
+        self.started = false;
+        self.done = false;
+        self
+    }
+
+    /// The directory being listed
+    pub fn dir(&self) -> &DataDir {
+        &self.dir
+    }
+
+    /// ACL of the directory, once the first page has been fetched (if ACLs are supported)
+    pub fn acl(&self) -> Option<&DataAcl> {
+        self.acl.as_ref()
+    }
+
+    /// Marker of the next page to fetch, if the listing has more pages
+    pub fn marker(&self) -> Option<&str> {
+        self.next_marker.as_deref()
+    }
+
+    /// Position of this listing, to resume it later
+    pub fn checkpoint(&self) -> ListCheckpoint {
+        let page_done = self.folders.len() == 0 && self.files.len() == 0;
+        match self.next_marker {
+            Some(ref marker) if page_done => ListCheckpoint {
+                marker: Some(marker.clone()),
+                offset: 0,
+            },
+            _ => ListCheckpoint {
+                marker: self.page_marker.clone(),
+                offset: self.offset,
+            },
+        }
+    }
+
+    fn fetch(&mut self) -> Result<(), Error> {
+        let marker = if self.started {
+            self.next_marker.clone()
+        } else {
+            self.page_marker.clone()
+        };
+        let options = ListOptions {
+            marker: marker.clone(),
+            limit: self.page_size,
+            filter: self.filter,
+            with_acl: !self.started,
+        };
+        let page = self.dir.backend().list(&self.dir.data_uri(), &options)?;
+
+        // Skip entries that were returned before the listing was resumed
+        let skip = if self.started { 0 } else { self.offset };
+        if !self.started {
+            self.acl = page.acl;
+            self.started = true;
+        }
+        let (mut folders, mut files) = (page.folders, page.files);
+        if !self.filter.folders() {
+            folders.clear();
+        }
+        if !self.filter.files() {
+            files.clear();
+        }
+        self.folders = folders.into_iter();
+        self.files = files.into_iter();
+        self.page_marker = marker;
+        self.next_marker = page.marker;
+        self.offset = 0;
+        while self.offset < skip && (self.folders.next().is_some() || self.files.next().is_some()) {
+            self.offset += 1;
         }
+        Ok(())
     }
 }
 
-impl<'a> Iterator for DirectoryListing<'a> {
+impl Iterator for Listing {
     type Item = Result<DataItem, Error>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        match self.folders.next() {
+        loop {
             // Return folders first
-            Some(d) => Some(Ok(DataItem::Dir(DataDirItem {
-                dir: self.dir.child(&d.name),
-            }))),
-            None => {
-                match self.files.next() {
-                    // Return files second
-                    Some(f) => Some(Ok(DataItem::File(DataFileItem {
-                        size: f.size,
-                        last_modified: f.last_modified,
-                        file: self.dir.child(&f.filename),
-                    }))),
-                    None => {
-                        // Query if there is another page of files/folders
-                        if self.query_count == 0 || self.marker.is_some() {
-                            self.query_count += 1;
-                            match get_directory(self.dir, self.marker.clone()) {
-                                Ok(ds) => {
-                                    self.folders = ds.folders.unwrap_or_else(Vec::new).into_iter();
-                                    self.files = ds.files.unwrap_or_else(Vec::new).into_iter();
-                                    self.marker = ds.marker;
-                                    self.next()
-                                }
-                                Err(err) => Some(Err(err)),
-                            }
-                        } else {
-                            None
-                        }
-                    }
-                }
+            if let Some(name) = self.folders.next() {
+                self.offset += 1;
+                return Some(Ok(DataItem::Dir(DataDirItem {
+                    dir: self.dir.child(&name),
+                })));
+            }
+            // Return files second
+            if let Some(f) = self.files.next() {
+                self.offset += 1;
+                return Some(Ok(DataItem::File(DataFileItem {
+                    size: f.size,
+                    last_modified: f.last_modified,
+                    file: self.dir.child(&f.name),
+                })));
+            }
+            // Query if there is another page of files/folders
+            if self.done || (self.started && self.next_marker.is_none()) {
+                return None;
+            }
+            if let Err(err) = self.fetch() {
+                self.done = true;
+                return Some(Err(err));
             }
         }
     }
 }
 
-fn get_directory(dir: &DataDir, marker: Option<String>) -> Result<DirectoryShow, Error> {
-    let mut url = dir.to_url()?;
-    if let Some(ref m) = marker {
-        url.query_pairs_mut().append_pair("marker", m);
-    }
-
-    let res = dir
-        .client
-        .get(url)
-        .send()
-        .with_context(|| format!("request error listing directory '{}'", dir.to_data_uri()))
-        .and_then(process_http_response)
-        .with_context(|| format!("response error listing directory '{}'", dir.to_data_uri()))?;
-
-    match res.headers().get(X_DATA_TYPE).map(lossy_header) {
-        Some(ref dt) if dt == "directory" => (),
-        data_type => {
-            let dt = data_type.unwrap_or_else(|| "unknown".to_string());
-            bail!("expected content type '{}', received '{}'", "directory", dt)
-        }
-    }
-
-    res.json().with_context(|| {
-        format!(
-            "JSON decoding error listing directory '{}'",
-            dir.to_data_uri()
-        )
-    })
-}
-
 impl HasDataPath for DataDir {
     #[doc(hidden)]
     fn new(client: HttpClient, path: &str) -> Self {
         DataDir {
             client: client,
-            path: parse_data_uri(path).to_string(),
+            path: DataUri::from(path).to_path(),
+            progress: None,
         }
     }
     #[doc(hidden)]
@@ -231,9 +365,34 @@ impl HasDataPath for DataDir {
     fn client(&self) -> &HttpClient {
         &self.client
     }
+    #[doc(hidden)]
+    fn set_progress_handler(&mut self, handler: Option<Arc<dyn ProgressHandler>>) {
+        self.progress = handler;
+    }
 }
 
 impl DataDir {
+    /// Builder method to report the progress of transfers within this directory
+    ///
+    /// Files and directories instantiated with `child` inherit the handler,
+    /// so progress is reported for every file of a recursive transfer.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use algorithmia::Algorithmia;
+    /// # use algorithmia::data::Progress;
+    /// let client = Algorithmia::client("111112222233333444445555566")?;
+    /// client
+    ///     .dir(".my/my_dir")
+    ///     .progress(|p: &Progress| println!("{}: {} bytes", p.data_uri, p.bytes))
+    ///     .put_file("/path/to/file")?;
+    /// # Ok::<(), Box<std::error::Error>>(())
+    /// ```
+    pub fn progress<P: ProgressHandler + 'static>(&mut self, handler: P) -> &mut DataDir {
+        self.progress = Some(Arc::new(handler));
+        self
+    }
+
     /// Display Directory details if it exists
     ///
     /// # Examples
@@ -253,7 +412,35 @@ impl DataDir {
     /// # Ok::<(), Box<std::error::Error>>(())
     /// ```
     pub fn list(&self) -> DirectoryListing<'_> {
-        DirectoryListing::new(self)
+        DirectoryListing {
+            acl: None,
+            listing: self.listing(),
+            _dir: PhantomData,
+        }
+    }
+
+    /// Owned listing of this Directory, which can be paged, filtered, and resumed
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use algorithmia::Algorithmia;
+    /// # use algorithmia::data::ListCheckpoint;
+    /// let client = Algorithmia::client("111112222233333444445555566")?;
+    /// let mut listing = client.dir(".my/my_dir").listing();
+    /// listing.page_size(1000);
+    /// for entry in listing.by_ref().take(5000) {
+    ///     entry?;
+    /// }
+    ///
+    /// // Save the position, e.g. as JSON, and continue from it later
+    /// let checkpoint = serde_json::to_string(&listing.checkpoint())?;
+    /// let checkpoint: ListCheckpoint = serde_json::from_str(&checkpoint)?;
+    /// let mut listing = client.dir(".my/my_dir").listing();
+    /// listing.page_size(1000).resume(checkpoint);
+    /// # Ok::<(), Box<std::error::Error>>(())
+    /// ```
+    pub fn listing(&self) -> Listing {
+        Listing::new(self.clone())
     }
 
     /// Create a Directory
@@ -273,39 +460,74 @@ impl DataDir {
     /// # Ok::<(), Box<std::error::Error>>(())
     /// ```
     pub fn create<Acl: Into<DataAcl>>(&self, acl: Acl) -> Result<(), Error> {
-        let parent = self.parent().ok_or_else(|| {
+        let connector = self.connector();
+        if connector.has_buckets() && self.data_uri().segments().len() == 1 {
+            bail!(
+                "cannot create bucket '{}': buckets on {} must be created with the storage provider",
+                self.to_data_uri(),
+                connector
+            );
+        }
+
+        let acl = acl.into();
+        let acl = if connector.supports_acl() {
+            Some(acl)
+        } else if acl == DataAcl::default() {
+            // Connectors without ACL support only accept the implied default
+            None
+        } else {
+            return Err(self.acl_unsupported());
+        };
+
+        self.backend().mkdir(&self.data_uri(), acl.as_ref())
+    }
+
+    /// Get the ACL of a Directory
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use algorithmia::Algorithmia;
+    /// # use algorithmia::data::{HasDataPath, ReadAcl};
+    /// let client = Algorithmia::client("111112222233333444445555566")?;
+    /// let my_dir = client.dir(".my/my_dir");
+    /// if ReadAcl::from(my_dir.acl()?) == ReadAcl::Public {
+    ///     println!("{} is publicly readable", my_dir.to_data_uri());
+    /// }
+    /// # Ok::<(), Box<std::error::Error>>(())
+    /// ```
+    pub fn acl(&self) -> Result<DataAcl, Error> {
+        if !self.connector().supports_acl() {
+            return Err(self.acl_unsupported());
+        }
+        let options = ListOptions {
+            with_acl: true,
+            ..ListOptions::default()
+        };
+        let page = self.backend().list(&self.data_uri(), &options)?;
+        page.acl.ok_or_else(|| {
             err_msg(format!(
-                "URI {} does not have a valid parent",
+                "storage backend returned no ACL for directory '{}'",
                 self.to_data_uri()
             ))
-        })?;
-        let parent_url = parent.to_url()?;
-
-        let input_data = FolderItem {
-            name: self
-                .basename()
-                .ok_or_else(|| {
-                    err_msg(format!(
-                        "Data URI {} does not have a valid basename",
-                        self.to_data_uri()
-                    ))
-                })?
-                .into(),
-            acl: Some(acl.into()),
-        };
-
-        // POST request
-        self.client
-            .post(parent_url)
-            .json(&input_data)
-            .send()
-            .with_context(|| format!("request error creating directory '{}'", self.to_data_uri()))
-            .and_then(process_http_response)
-            .with_context(|| {
-                format!("response error creating directory '{}'", self.to_data_uri())
-            })?;
+        })
+    }
 
-        Ok(())
+    /// Update the ACL of an existing Directory
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use algorithmia::Algorithmia;
+    /// # use algorithmia::data::ReadAcl;
+    /// let client = Algorithmia::client("111112222233333444445555566")?;
+    /// let my_dir = client.dir(".my/my_dir");
+    /// my_dir.set_acl(ReadAcl::Private)?;
+    /// # Ok::<(), Box<std::error::Error>>(())
+    /// ```
+    pub fn set_acl<Acl: Into<DataAcl>>(&self, acl: Acl) -> Result<(), Error> {
+        if !self.connector().supports_acl() {
+            return Err(self.acl_unsupported());
+        }
+        self.backend().set_acl(&self.data_uri(), &acl.into())
     }
 
     /// Delete a Directory
@@ -322,31 +544,11 @@ impl DataDir {
     /// # Ok::<(), Box<std::error::Error>>(())
     /// ```
     pub fn delete(&self, force: bool) -> Result<DirectoryDeleted, Error> {
-        // DELETE request
-        let mut url = self.to_url()?;
-        if force {
-            url.query_pairs_mut().append_pair("force", "true");
-        }
-
-        // Parse response
-        let res = self
-            .client
-            .delete(url)
-            .send()
-            .with_context(|| format!("request error deleting directory '{}'", self.to_data_uri()))
-            .and_then(process_http_response)
-            .with_context(|| {
-                format!("response error deleting directory '{}'", self.to_data_uri())
-            })?;
-
-        res.json::<DeletedResponse>()
-            .map(|res| res.result)
-            .with_context(|| {
-                format!(
-                    "JSON decoding error deleting directory '{}'",
-                    self.to_data_uri()
-                )
-            })
+        let deleted = self.backend().delete_dir(&self.data_uri(), force)?;
+        Ok(DirectoryDeleted {
+            deleted,
+            _dummy: (),
+        })
     }
 
     /// Upload a file to an existing Directory
@@ -365,22 +567,147 @@ impl DataDir {
     /// ```
     pub fn put_file<P: AsRef<Path>>(&self, file_path: P) -> Result<(), Error> {
         let path_ref = file_path.as_ref();
-        let file = File::open(path_ref)
-            .with_context(|| format!("opening file for upload '{}'", path_ref.display()))?;
-
-        // Safe to unwrap: we've already opened the file or returned an error
-        let filename = path_ref.file_name().unwrap().to_string_lossy();
+        let filename = match path_ref.file_name() {
+            Some(filename) => filename.to_string_lossy(),
+            None => bail!("'{}' is not a path to a file", path_ref.display()),
+        };
         let data_file: DataFile = self.child(&filename);
-        data_file.put(file)
+        data_file.put_file(path_ref)
+    }
+
+    fn acl_unsupported(&self) -> Error {
+        err_msg(format!(
+            "cannot manage ACL of '{}': the {} connector does not support ACLs",
+            self.to_data_uri(),
+            self.connector()
+        ))
+    }
+
+    /// Find the files and directories below this Directory matching a glob pattern
+    ///
+    /// The pattern is relative to this Directory, whose own path is never treated as a pattern,
+    /// even if it contains wildcard characters. See
+    /// [`Algorithmia::glob`](../struct.Algorithmia.html#method.glob) for the pattern syntax.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use algorithmia::Algorithmia;
+    /// # use algorithmia::data::{DataItem, HasDataPath};
+    /// let client = Algorithmia::client("111112222233333444445555566")?;
+    /// for item in client.dir(".my/runs[2026]").glob("**/*.csv") {
+    ///     if let DataItem::File(f) = item? {
+    ///         println!("{}", f.to_data_uri());
+    ///     }
+    /// }
+    /// # Ok::<(), Box<std::error::Error>>(())
+    /// ```
+    pub fn glob(&self, pattern: &str) -> Glob {
+        Glob::under(self.client.clone(), &self.data_uri(), pattern)
+    }
+
+    /// Delete files within this Directory and its subdirectories
+    ///
+    /// Directories are left in place. See [`BulkDelete`](struct.BulkDelete.html) for filters.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use algorithmia::Algorithmia;
+    /// let client = Algorithmia::client("111112222233333444445555566")?;
+    /// let summary = client
+    ///     .dir(".my/my_dir")
+    ///     .bulk_delete()
+    ///     .filter(|f| f.size == 0)
+    ///     .run()?;
+    /// println!("deleted {} empty files", summary.deleted.len());
+    /// # Ok::<(), Box<std::error::Error>>(())
+    /// ```
+    pub fn bulk_delete(&self) -> BulkDelete {
+        BulkDelete::under(self.client.clone(), self.data_uri(), "**/*")
+    }
+
+    /// Summarize the disk usage of this Directory and its subdirectories
+    ///
+    /// Returns a [`UsageBuilder`](struct.UsageBuilder.html) to configure the breakdown
+    /// before calling `run`.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use algorithmia::Algorithmia;
+    /// let client = Algorithmia::client("111112222233333444445555566")?;
+    /// let usage = client.dir(".my/my_dir").usage().depth(2).run()?;
+    /// println!("{} files, {} bytes", usage.files, usage.bytes);
+    /// # Ok::<(), Box<std::error::Error>>(())
+    /// ```
+    pub fn usage(&self) -> UsageBuilder {
+        UsageBuilder::new(self.clone())
+    }
+
+    /// Watch this Directory for created, modified, and deleted files
+    ///
+    /// Returns a [`Watcher`](struct.Watcher.html) that polls the directory when iterated.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use algorithmia::Algorithmia;
+    /// let client = Algorithmia::client("111112222233333444445555566")?;
+    /// for event in client.dir(".my/inbox").watch().recursive(true) {
+    ///     let event = event?;
+    ///     println!("{:?}: {}", event.kind, event.data_uri);
+    /// }
+    /// # Ok::<(), Box<std::error::Error>>(())
+    /// ```
+    pub fn watch(&self) -> Watcher {
+        Watcher::new(self.clone())
+    }
+
+    /// Create a uniquely named subdirectory that is deleted when dropped
+    ///
+    /// The name starts with `prefix`. See [`TempDataDir`](struct.TempDataDir.html).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use algorithmia::Algorithmia;
+    /// # use algorithmia::data::HasDataPath;
+    /// let client = Algorithmia::client("111112222233333444445555566")?;
+    /// let scratch = client.dir(".my/jobs").create_temp("job-")?;
+    /// println!("created {}", scratch.to_data_uri());
+    /// # Ok::<(), Box<std::error::Error>>(())
+    /// ```
+    pub fn create_temp(&self, prefix: &str) -> Result<TempDataDir, Error> {
+        TempDataDir::new_in(self, prefix)
     }
 
     /// Instantiate `DataFile` or `DataDir` as a child of this `DataDir`
+    ///
+    /// `filename` may be a relative path of several names, e.g. `sub/file.txt`.
+    /// `..` segments never go above this directory: use `try_child` to reject
+    /// `.` and `..` segments in untrusted names instead.
     pub fn child<T: HasDataPath>(&self, filename: &str) -> T {
-        let new_uri = match self.to_data_uri() {
-            ref uri if uri.ends_with('/') => format!("{}{}", uri, filename),
-            uri => format!("{}/{}", uri, filename),
-        };
-        T::new(self.client.clone(), &new_uri)
+        self.child_at(&self.data_uri().join_below(filename))
+    }
+
+    /// Instantiate `DataFile` or `DataDir` as a child of this `DataDir`,
+    /// failing if `filename` has `.` or `..` segments
+    ///
+    /// # Examples
+    /// ```
+    /// # use algorithmia::Algorithmia;
+    /// # use algorithmia::data::{DataFile, HasDataPath};
+    /// let client = Algorithmia::client("111112222233333444445555566")?;
+    /// let uploads = client.dir(".my/uploads");
+    /// let file: DataFile = uploads.try_child("report.csv")?;
+    /// assert_eq!(file.to_data_uri(), "data://.my/uploads/report.csv");
+    /// assert!(uploads.try_child::<DataFile>("../secrets.csv").is_err());
+    /// # Ok::<(), Box<std::error::Error>>(())
+    /// ```
+    pub fn try_child<T: HasDataPath>(&self, filename: &str) -> Result<T, Error> {
+        Ok(self.child_at(&self.data_uri().join_child(filename)?))
+    }
+
+    fn child_at<T: HasDataPath>(&self, uri: &DataUri) -> T {
+        let mut child = T::new(self.client.clone(), &uri.to_string());
+        child.set_progress_handler(self.progress.clone());
+        child
     }
 }
 
@@ -427,6 +754,25 @@ mod tests {
         assert!(dir.parent().is_none());
     }
 
+    #[test]
+    fn test_child() {
+        let dir = mock_client().dir("data://anowell/foo");
+        let file: DataFile = dir.child("bar/baz.txt");
+        assert_eq!(file.to_data_uri(), "data://anowell/foo/bar/baz.txt");
+    }
+
+    #[test]
+    fn test_child_outside_dir() {
+        let dir = mock_client().dir("data://anowell/foo");
+        let file: DataFile = dir.child("../secret.txt");
+        assert_eq!(file.to_data_uri(), "data://anowell/foo/secret.txt");
+        let file: DataFile = dir.child("sub/../../../secret.txt");
+        assert_eq!(file.to_data_uri(), "data://anowell/foo/secret.txt");
+        assert!(dir.try_child::<DataFile>("../secret.txt").is_err());
+        assert!(dir.try_child::<DataFile>("sub/./secret.txt").is_err());
+        assert!(dir.create_temp("../").is_err());
+    }
+
     #[test]
     fn test_default_acl() {
         let acl: DataAcl = DataAcl::default();
@@ -450,4 +796,40 @@ mod tests {
         let acl: DataAcl = ReadAcl::MyAlgorithms.into();
         assert_eq!(acl.read, vec!["algo://.my/*".to_string()]);
     }
+
+    #[test]
+    fn test_read_acl_from_data_acl() {
+        assert_eq!(
+            ReadAcl::from(DataAcl::from(ReadAcl::Private)),
+            ReadAcl::Private
+        );
+        assert_eq!(
+            ReadAcl::from(DataAcl::from(ReadAcl::Public)),
+            ReadAcl::Public
+        );
+        assert_eq!(ReadAcl::from(DataAcl::default()), ReadAcl::MyAlgorithms);
+
+        let custom = vec!["user://*".to_string(), "algo://.my/*".to_string()];
+        let acl = DataAcl::from(ReadAcl::Custom(custom.clone()));
+        assert_eq!(ReadAcl::from(acl), ReadAcl::Custom(custom));
+    }
+
+    #[test]
+    fn test_connector_acl_restrictions() {
+        let dir = mock_client().dir("dropbox://foo/bar");
+        assert!(dir.acl().is_err());
+        assert!(dir.set_acl(ReadAcl::Public).is_err());
+        assert!(dir.create(ReadAcl::Public).is_err());
+    }
+
+    #[test]
+    fn test_create_bucket() {
+        for path in &["s3://my-bucket", "s3://my-bucket/", "s3://./my-bucket//"] {
+            let err = mock_client()
+                .dir(*path)
+                .create(DataAcl::default())
+                .unwrap_err();
+            assert!(err.to_string().contains("cannot create bucket"), "{}", err);
+        }
+    }
 }
@@ -0,0 +1,595 @@
+//! Client-side encryption of Algorithmia Data Files
+//!
+//! Content is encrypted with XChaCha20-Poly1305 before it is uploaded, so the Data API
+//! only ever stores ciphertext. Content is encrypted and authenticated in chunks
+//! of 64 KiB, so files of any size are streamed without being held in memory,
+//! and truncated or reordered chunks are detected as well as corrupted ones.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use algorithmia::Algorithmia;
+//! use algorithmia::data::{EncryptionKey, EnvKey};
+//! use std::fs::File;
+//!
+//! let client = Algorithmia::client("111112222233333444445555566")?;
+//!
+//! // Encrypt with a key read from the ALGORITHMIA_DATA_KEY environment variable
+//! let secrets = client.file(".my/my_dir/secrets.bin").encrypted(EnvKey::new("ALGORITHMIA_DATA_KEY"));
+//! secrets.put(File::open("/path/to/secrets.bin")?)?;
+//! let plaintext = secrets.get()?.into_bytes()?;
+//!
+//! // Or with a key held by the application
+//! let key = EncryptionKey::generate();
+//! println!("keep this key safe: {}", key.to_base64());
+//! client.file(".my/my_dir/notes.txt").encrypted(key).put(&b"for my eyes only"[..])?;
+//! # Ok::<(), Box<std::error::Error>>(())
+//! ```
+
+use crate::data::{DataFile, DataUri, Direction, FileData, HasDataPath, ProgressReader};
+use crate::error::{Error, ResultExt};
+use crate::Body;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, KeyInit, OsRng, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use std::error::Error as StdError;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+use std::sync::Arc;
+use std::{env, fmt};
+
+/// Number of bytes identifying the format of encrypted content
+const MAGIC_SIZE: usize = 8;
+
+/// Identifies (and versions) the format of encrypted content
+const MAGIC: &[u8; MAGIC_SIZE] = b"ALGOENC1";
+
+/// Number of plaintext bytes encrypted per chunk
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Largest chunk size accepted when decrypting
+const MAX_CHUNK_SIZE: usize = 16 * 1024 * 1024;
+
+/// Number of bytes in the authentication tag of each chunk
+const TAG_SIZE: usize = 16;
+
+/// Number of random bytes in the nonce of each chunk
+///
+/// XChaCha20's 192-bit nonces leave room for a random prefix long enough that
+/// files encrypted with the same key never realistically share one.
+const NONCE_PREFIX_SIZE: usize = 19;
+
+/// Magic, chunk size, and nonce prefix
+const HEADER_SIZE: usize = MAGIC_SIZE + 4 + NONCE_PREFIX_SIZE;
+
+/// 256-bit key for encrypting data files
+#[derive(Clone, PartialEq, Eq)]
+pub struct EncryptionKey([u8; 32]);
+
+impl EncryptionKey {
+    /// Generate a random key from the operating system's secure random number generator
+    pub fn generate() -> EncryptionKey {
+        let mut key = [0; 32];
+        OsRng.fill_bytes(&mut key);
+        EncryptionKey(key)
+    }
+
+    /// Create a key from its raw bytes
+    pub fn from_bytes(bytes: [u8; 32]) -> EncryptionKey {
+        EncryptionKey(bytes)
+    }
+
+    /// Decode a key from base64 (e.g. as produced by `to_base64`)
+    pub fn from_base64(encoded: &str) -> Result<EncryptionKey, Error> {
+        let bytes = base64::decode(encoded.trim()).context("invalid base64 encryption key")?;
+        if bytes.len() != 32 {
+            bail!(
+                "encryption key must be 32 bytes, found {} bytes",
+                bytes.len()
+            );
+        }
+        let mut key = [0; 32];
+        key.copy_from_slice(&bytes);
+        Ok(EncryptionKey(key))
+    }
+
+    /// Encode the key as base64
+    pub fn to_base64(&self) -> String {
+        base64::encode(&self.0)
+    }
+
+    fn cipher(&self) -> XChaCha20Poly1305 {
+        XChaCha20Poly1305::new(&self.0.into())
+    }
+}
+
+impl fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("EncryptionKey(..)")
+    }
+}
+
+/// Source of the key used to encrypt and decrypt a data file
+///
+/// Implemented for `EncryptionKey`, `EnvKey`, and for closures that look up
+/// the key of a file (e.g. from a secrets manager).
+///
+/// # Examples
+/// ```no_run
+/// # use algorithmia::Algorithmia;
+/// # use algorithmia::data::{DataUri, EncryptionKey};
+/// # use std::error::Error;
+/// # fn fetch_secret(name: &str) -> Result<String, Box<Error + Send + Sync>> { unimplemented!() }
+/// let client = Algorithmia::client("111112222233333444445555566")?;
+/// let per_owner = |uri: &DataUri| -> Result<EncryptionKey, Box<Error + Send + Sync>> {
+///     let secret = fetch_secret(uri.owner().unwrap_or("shared"))?;
+///     Ok(EncryptionKey::from_base64(&secret)?)
+/// };
+/// let data = client.file(".my/my_dir/report.csv").encrypted(per_owner).get()?;
+/// # Ok::<(), Box<std::error::Error>>(())
+/// ```
+pub trait KeyProvider: Send + Sync {
+    /// Get the key for the file at `data_uri`
+    fn key(&self, data_uri: &DataUri) -> Result<EncryptionKey, Box<dyn StdError + Send + Sync>>;
+}
+
+impl KeyProvider for EncryptionKey {
+    fn key(&self, _: &DataUri) -> Result<EncryptionKey, Box<dyn StdError + Send + Sync>> {
+        Ok(self.clone())
+    }
+}
+
+impl<F> KeyProvider for F
+where
+    F: Fn(&DataUri) -> Result<EncryptionKey, Box<dyn StdError + Send + Sync>> + Send + Sync,
+{
+    fn key(&self, data_uri: &DataUri) -> Result<EncryptionKey, Box<dyn StdError + Send + Sync>> {
+        self(data_uri)
+    }
+}
+
+/// Key provider that reads a base64 encoded key from an environment variable
+#[derive(Debug, Clone)]
+pub struct EnvKey {
+    var: String,
+}
+
+impl EnvKey {
+    /// Read the key from the environment variable `var`
+    pub fn new<S: Into<String>>(var: S) -> EnvKey {
+        EnvKey { var: var.into() }
+    }
+}
+
+impl KeyProvider for EnvKey {
+    fn key(&self, _: &DataUri) -> Result<EncryptionKey, Box<dyn StdError + Send + Sync>> {
+        let encoded = env::var(&self.var)
+            .map_err(|err| format!("cannot read encryption key from '{}': {}", self.var, err))?;
+        Ok(EncryptionKey::from_base64(&encoded)?)
+    }
+}
+
+/// Error when encrypted content fails authentication
+///
+/// The content was corrupted, truncated, or encrypted with a different key.
+/// It is surfaced as an `io::Error` of kind `InvalidData` while reading decrypted data.
+/// `EncryptedFile::get_bytes` and `EncryptedFile::download_to_path` return it in an
+/// `algorithmia::Error`, where `Error::is_authentication_failure` detects it.
+#[derive(Debug)]
+pub struct AuthenticationError {
+    data_uri: String,
+}
+
+impl fmt::Display for AuthenticationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "encrypted file '{}' failed authentication: it is corrupted, truncated, or was encrypted with a different key",
+            self.data_uri
+        )
+    }
+}
+
+impl StdError for AuthenticationError {}
+
+/// `DataFile` that encrypts its content on upload and decrypts it on download
+///
+/// Created with [`DataFile::encrypted`](struct.DataFile.html#method.encrypted).
+/// The `size` of downloaded `FileData` is the stored (encrypted) size.
+#[derive(Clone)]
+pub struct EncryptedFile {
+    file: DataFile,
+    keys: Arc<dyn KeyProvider>,
+}
+
+impl DataFile {
+    /// Encrypt and decrypt the content of this file on the client with keys from `keys`
+    ///
+    /// Content is encrypted with XChaCha20-Poly1305 in authenticated chunks of 64 KiB,
+    /// so it is streamed in both directions and truncation is detected.
+    pub fn encrypted<K: KeyProvider + 'static>(&self, keys: K) -> EncryptedFile {
+        EncryptedFile {
+            file: self.clone(),
+            keys: Arc::new(keys),
+        }
+    }
+}
+
+impl EncryptedFile {
+    /// The underlying data file, whose content is the encrypted bytes
+    pub fn file(&self) -> &DataFile {
+        &self.file
+    }
+
+    /// Encrypt the content of a reader while uploading it
+    pub fn put<R: Read + Send + 'static>(&self, reader: R) -> Result<(), Error> {
+        let cipher = self.key()?.cipher();
+        let reader = EncryptingReader::new(reader, cipher);
+        match self.file.tracker(Direction::Upload, None, 0) {
+            Some(tracker) => self
+                .file
+                .put_raw(Body::new(ProgressReader::new(reader, tracker))),
+            None => self.file.put_raw(Body::new(reader)),
+        }
+    }
+
+    /// Download and decrypt the content of this file
+    ///
+    /// Content is decrypted as it is read. Reading returns an `AuthenticationError`
+    /// (as an `io::Error` of kind `InvalidData`) as soon as a chunk fails authentication,
+    /// and no bytes from that chunk are returned.
+    pub fn get(&self) -> Result<FileData, Error> {
+        let key = self.key()?;
+        let data = self.file.request_file(0, None)?;
+        let data = self.file.track_download(data);
+        let data_uri = self.file.to_data_uri();
+        Ok(data.map_reader(|reader| Box::new(DecryptingReader::new(reader, key, data_uri))))
+    }
+
+    /// Download and decrypt the content of this file into memory
+    ///
+    /// Use `Error::is_authentication_failure` to tell content that failed authentication
+    /// apart from other errors.
+    pub fn get_bytes(&self) -> Result<Vec<u8>, Error> {
+        self.get()?
+            .into_bytes()
+            .with_context(|| format!("error decrypting file '{}'", self.file.to_data_uri()))
+    }
+
+    /// Download and decrypt the content of this file to a local file, returning its size
+    ///
+    /// The local file is created or truncated. Unlike `DataFile::download_to_path`,
+    /// interrupted downloads are not resumed. Use `Error::is_authentication_failure`
+    /// to tell content that failed authentication apart from other errors.
+    pub fn download_to_path<P: AsRef<Path>>(&self, local_path: P) -> Result<u64, Error> {
+        let local_path = local_path.as_ref();
+        let mut data = self.get()?;
+        let mut local = File::create(local_path)
+            .with_context(|| format!("creating '{}' for download", local_path.display()))?;
+        io::copy(&mut data, &mut local).with_context(|| {
+            format!(
+                "error decrypting file '{}' to '{}'",
+                self.file.to_data_uri(),
+                local_path.display()
+            )
+        })
+    }
+
+    fn key(&self) -> Result<EncryptionKey, Error> {
+        self.keys.key(&self.file.data_uri()).with_context(|| {
+            format!(
+                "error getting encryption key for '{}'",
+                self.file.to_data_uri()
+            )
+        })
+    }
+}
+
+fn chunk_nonce(prefix: &[u8], counter: u32, last: bool) -> XNonce {
+    let mut nonce = [0; 24];
+    nonce[..NONCE_PREFIX_SIZE].copy_from_slice(prefix);
+    nonce[NONCE_PREFIX_SIZE..23].copy_from_slice(&counter.to_be_bytes());
+    nonce[23] = last as u8;
+    nonce.into()
+}
+
+/// Read as much as possible into `buf`, stopping early only at EOF
+fn read_full<R: Read>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(ref err) if err.kind() == io::ErrorKind::Interrupted => (),
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(filled)
+}
+
+/// Reader of the encrypted content of a plaintext reader
+///
+/// Every chunk but the last holds `CHUNK_SIZE` plaintext bytes,
+/// so the last chunk is always shorter (and may be empty).
+struct EncryptingReader<R> {
+    inner: R,
+    cipher: XChaCha20Poly1305,
+    header: [u8; HEADER_SIZE],
+    counter: u32,
+    plaintext: Vec<u8>,
+    out: Vec<u8>,
+    pos: usize,
+    done: bool,
+}
+
+impl<R: Read> EncryptingReader<R> {
+    fn new(inner: R, cipher: XChaCha20Poly1305) -> EncryptingReader<R> {
+        let mut header = [0; HEADER_SIZE];
+        header[..MAGIC.len()].copy_from_slice(MAGIC);
+        header[MAGIC.len()..MAGIC.len() + 4].copy_from_slice(&(CHUNK_SIZE as u32).to_be_bytes());
+        OsRng.fill_bytes(&mut header[MAGIC.len() + 4..]);
+        EncryptingReader {
+            inner,
+            cipher,
+            header,
+            counter: 0,
+            plaintext: vec![0; CHUNK_SIZE],
+            out: header.to_vec(),
+            pos: 0,
+            done: false,
+        }
+    }
+
+    fn next_chunk(&mut self) -> io::Result<()> {
+        let len = read_full(&mut self.inner, &mut self.plaintext)?;
+        let last = len < CHUNK_SIZE;
+        let nonce = chunk_nonce(&self.header[MAGIC.len() + 4..], self.counter, last);
+        let payload = Payload {
+            msg: &self.plaintext[..len],
+            aad: &self.header,
+        };
+        self.out = self
+            .cipher
+            .encrypt(&nonce, payload)
+            .map_err(|_| io::Error::other("encryption error"))?;
+        self.pos = 0;
+        self.done = last;
+        self.counter = match self.counter.checked_add(1) {
+            Some(counter) => counter,
+            None if last => self.counter,
+            None => return Err(io::Error::other("file is too large to encrypt")),
+        };
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for EncryptingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos == self.out.len() {
+            if self.done {
+                return Ok(0);
+            }
+            self.next_chunk()?;
+        }
+        let n = (&self.out[self.pos..]).read(buf)?;
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+/// Reader of the plaintext content of an encrypted reader
+struct DecryptingReader<R> {
+    inner: R,
+    key: EncryptionKey,
+    data_uri: String,
+    state: Option<DecryptState>,
+    out: Vec<u8>,
+    pos: usize,
+    done: bool,
+}
+
+struct DecryptState {
+    cipher: XChaCha20Poly1305,
+    header: [u8; HEADER_SIZE],
+    chunk: Vec<u8>,
+    counter: u32,
+}
+
+impl<R: Read> DecryptingReader<R> {
+    fn new(inner: R, key: EncryptionKey, data_uri: String) -> DecryptingReader<R> {
+        DecryptingReader {
+            inner,
+            key,
+            data_uri,
+            state: None,
+            out: Vec::new(),
+            pos: 0,
+            done: false,
+        }
+    }
+
+    fn authentication_error(&self) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            AuthenticationError {
+                data_uri: self.data_uri.clone(),
+            },
+        )
+    }
+
+    fn read_header(&mut self) -> io::Result<DecryptState> {
+        let mut header = [0; HEADER_SIZE];
+        let len = read_full(&mut self.inner, &mut header)?;
+        if len < HEADER_SIZE || &header[..MAGIC.len()] != MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("file '{}' is not an encrypted data file", self.data_uri),
+            ));
+        }
+        let mut chunk_size = [0; 4];
+        chunk_size.copy_from_slice(&header[MAGIC.len()..MAGIC.len() + 4]);
+        let chunk_size = u32::from_be_bytes(chunk_size) as usize;
+        if chunk_size == 0 || chunk_size > MAX_CHUNK_SIZE {
+            return Err(self.authentication_error());
+        }
+        Ok(DecryptState {
+            cipher: self.key.cipher(),
+            header,
+            chunk: vec![0; chunk_size + TAG_SIZE],
+            counter: 0,
+        })
+    }
+
+    fn next_chunk(&mut self) -> io::Result<()> {
+        let mut state = match self.state.take() {
+            Some(state) => state,
+            None => self.read_header()?,
+        };
+        let len = read_full(&mut self.inner, &mut state.chunk)?;
+        // A missing final chunk means the content was truncated
+        if len < TAG_SIZE {
+            return Err(self.authentication_error());
+        }
+        let last = len < state.chunk.len();
+        let nonce = chunk_nonce(&state.header[MAGIC.len() + 4..], state.counter, last);
+        let payload = Payload {
+            msg: &state.chunk[..len],
+            aad: &state.header,
+        };
+        self.out = match state.cipher.decrypt(&nonce, payload) {
+            Ok(plaintext) => plaintext,
+            Err(_) => return Err(self.authentication_error()),
+        };
+        self.pos = 0;
+        self.done = last;
+        state.counter = state.counter.wrapping_add(1);
+        self.state = Some(state);
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for DecryptingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos == self.out.len() {
+            if self.done {
+                return Ok(0);
+            }
+            self.next_chunk()?;
+        }
+        let n = (&self.out[self.pos..]).read(buf)?;
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn encrypt(key: &EncryptionKey, plaintext: &[u8]) -> Vec<u8> {
+        let mut ciphertext = Vec::new();
+        EncryptingReader::new(Cursor::new(plaintext.to_vec()), key.cipher())
+            .read_to_end(&mut ciphertext)
+            .unwrap();
+        ciphertext
+    }
+
+    fn decrypt(key: &EncryptionKey, ciphertext: Vec<u8>) -> io::Result<Vec<u8>> {
+        let mut plaintext = Vec::new();
+        DecryptingReader::new(
+            Cursor::new(ciphertext),
+            key.clone(),
+            "data://.my/foo".into(),
+        )
+        .read_to_end(&mut plaintext)?;
+        Ok(plaintext)
+    }
+
+    fn is_authentication_error(err: &io::Error) -> bool {
+        err.get_ref()
+            .is_some_and(|inner| inner.is::<AuthenticationError>())
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let key = EncryptionKey::generate();
+        for &len in &[0, 1, CHUNK_SIZE - 1, CHUNK_SIZE, 2 * CHUNK_SIZE + 7] {
+            let plaintext: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            let ciphertext = encrypt(&key, &plaintext);
+            let chunks = len / CHUNK_SIZE + 1;
+            assert_eq!(ciphertext.len(), HEADER_SIZE + len + chunks * TAG_SIZE);
+            assert_eq!(decrypt(&key, ciphertext).unwrap(), plaintext);
+        }
+    }
+
+    #[test]
+    fn test_wrong_key() {
+        let ciphertext = encrypt(&EncryptionKey::generate(), b"secret");
+        let err = decrypt(&EncryptionKey::generate(), ciphertext).unwrap_err();
+        assert!(is_authentication_error(&err));
+    }
+
+    #[test]
+    fn test_tampered_and_truncated() {
+        let key = EncryptionKey::generate();
+        let ciphertext = encrypt(&key, &vec![7; 2 * CHUNK_SIZE + 100]);
+
+        let mut tampered = ciphertext.clone();
+        tampered[HEADER_SIZE + 10] ^= 1;
+        assert!(is_authentication_error(
+            &decrypt(&key, tampered).unwrap_err()
+        ));
+
+        // Dropping the final chunk leaves a valid-looking sequence of full chunks
+        let truncated = ciphertext[..HEADER_SIZE + 2 * (CHUNK_SIZE + TAG_SIZE)].to_vec();
+        assert!(is_authentication_error(
+            &decrypt(&key, truncated).unwrap_err()
+        ));
+
+        let mut reordered = ciphertext[..HEADER_SIZE].to_vec();
+        reordered.extend_from_slice(
+            &ciphertext[HEADER_SIZE + CHUNK_SIZE + TAG_SIZE..][..CHUNK_SIZE + TAG_SIZE],
+        );
+        reordered.extend_from_slice(&ciphertext[HEADER_SIZE..][..CHUNK_SIZE + TAG_SIZE]);
+        reordered.extend_from_slice(&ciphertext[HEADER_SIZE + 2 * (CHUNK_SIZE + TAG_SIZE)..]);
+        assert!(is_authentication_error(
+            &decrypt(&key, reordered).unwrap_err()
+        ));
+    }
+
+    #[test]
+    fn test_not_encrypted() {
+        let err = decrypt(&EncryptionKey::generate(), b"plain text content".to_vec()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(!is_authentication_error(&err));
+    }
+
+    #[test]
+    fn test_encrypted_file() {
+        let mut client = crate::Algorithmia::client("").unwrap();
+        client.backend(crate::data::MemoryBackend::new());
+        let key = EncryptionKey::generate();
+        let file = client.file(".my/secret.bin");
+        file.encrypted(key.clone())
+            .put(Cursor::new("secret"))
+            .unwrap();
+        assert_ne!(file.get().unwrap().into_bytes().unwrap(), b"secret");
+        assert_eq!(file.encrypted(key).get_bytes().unwrap(), b"secret");
+
+        let err = file
+            .encrypted(EncryptionKey::generate())
+            .get_bytes()
+            .unwrap_err();
+        assert!(err.is_authentication_failure());
+    }
+
+    #[test]
+    fn test_key_encoding() {
+        let key = EncryptionKey::generate();
+        assert_eq!(EncryptionKey::from_base64(&key.to_base64()).unwrap(), key);
+        assert!(EncryptionKey::from_base64("c2hvcnQ=").is_err());
+        assert_eq!(format!("{:?}", key), "EncryptionKey(..)");
+    }
+}
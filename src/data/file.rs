@@ -11,13 +11,33 @@
 //! # Ok::<(), Box<std::error::Error>>(())
 //! ```
 
-use super::{algo_epoch, parse_data_uri, parse_headers};
 use crate::client::HttpClient;
-use crate::data::{DataType, HasDataPath};
-use crate::error::{process_http_response, Error, ResultExt};
+#[cfg(any(feature = "gzip", feature = "zstd"))]
+use crate::data::compression::Compression;
+use crate::data::{
+    DataType, DataUri, Direction, FileWriter, HasDataPath, ObjectMetadata, ProgressHandler,
+    ProgressReader, ProgressTracker, SeekableFile,
+};
+use crate::error::{Error, ResultExt};
 use crate::Body;
 use chrono::{DateTime, Utc};
-use std::io::{self, Read};
+use mime::Mime;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, Read, Seek, SeekFrom, Write};
+use std::ops::{Bound, RangeBounds};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// Number of times `download_to_path` retries an interrupted transfer
+const DOWNLOAD_RETRIES: u32 = 3;
+
+/// Distinguishes temporary files of concurrent `put_atomic` calls in this process
+static ATOMIC_COUNTER: AtomicUsize = AtomicUsize::new(0);
 
 /// Response and reader when downloading a `DataFile`
 pub struct FileData {
@@ -25,6 +45,10 @@ pub struct FileData {
     pub size: u64,
     /// Last modified timestamp
     pub last_modified: DateTime<Utc>,
+    /// Entity tag identifying this version of the file, if reported by the storage backend
+    pub etag: Option<String>,
+    /// Whether `last_modified` was reported by the storage backend, rather than a fallback
+    pub(crate) last_modified_known: bool,
     data: Box<dyn Read>,
 }
 
@@ -36,6 +60,59 @@ impl Read for FileData {
 }
 
 impl FileData {
+    /// Create `FileData` reading `size` bytes of content from `data`
+    ///
+    /// Used by storage backends to return downloaded content.
+    pub fn new<R: Read + 'static>(data: R, size: u64, last_modified: DateTime<Utc>) -> FileData {
+        FileData {
+            size,
+            last_modified,
+            etag: None,
+            last_modified_known: true,
+            data: Box::new(data),
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn from_bytes(bytes: Vec<u8>) -> FileData {
+        FileData {
+            size: bytes.len() as u64,
+            last_modified: super::algo_epoch(),
+            etag: None,
+            last_modified_known: true,
+            data: Box::new(io::Cursor::new(bytes)),
+        }
+    }
+
+    /// Wrap the reader of the file content, e.g. to decode it
+    pub(crate) fn map_reader<F>(self, f: F) -> FileData
+    where
+        F: FnOnce(Box<dyn Read>) -> Box<dyn Read>,
+    {
+        FileData {
+            size: self.size,
+            last_modified: self.last_modified,
+            etag: self.etag,
+            last_modified_known: self.last_modified_known,
+            data: f(self.data),
+        }
+    }
+
+    /// Wrap the reader of the file content with a reader that may fail to be created
+    #[cfg(any(feature = "gzip", feature = "zstd"))]
+    pub(crate) fn try_map_reader<F>(self, f: F) -> Result<FileData, Error>
+    where
+        F: FnOnce(Box<dyn Read>) -> Result<Box<dyn Read>, Error>,
+    {
+        Ok(FileData {
+            size: self.size,
+            last_modified: self.last_modified,
+            etag: self.etag,
+            last_modified_known: self.last_modified_known,
+            data: f(self.data)?,
+        })
+    }
+
     /// Reads the result into a byte vector
     ///
     /// This is a convenience wrapper around `Read::read_to_end`
@@ -57,10 +134,43 @@ impl FileData {
     }
 }
 
+/// Reader over the content of an in-memory `Body`, without copying it
+struct BodyBytes {
+    body: Body,
+    pos: usize,
+}
+
+impl Read for BodyBytes {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let bytes = self.body.as_bytes().unwrap_or_default();
+        let n = (&bytes[self.pos.min(bytes.len())..]).read(buf)?;
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+/// Metadata of a `DataFile`, retrieved without downloading its content
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct FileMetadata {
+    /// Size of file in bytes
+    pub size: u64,
+    /// Last modified timestamp, if reported by the storage backend
+    pub last_modified: Option<DateTime<Utc>>,
+    /// Content type reported by the API, if any
+    pub content_type: Option<Mime>,
+    /// Entity tag identifying this version of the file, if any
+    pub etag: Option<String>,
+}
+
 /// Algorithmia data file
+#[derive(Clone)]
 pub struct DataFile {
     path: String,
     client: HttpClient,
+    progress: Option<Arc<dyn ProgressHandler>>,
+    #[cfg(any(feature = "gzip", feature = "zstd"))]
+    pub(crate) compression: Option<Compression>,
 }
 
 impl HasDataPath for DataFile {
@@ -68,7 +178,10 @@ impl HasDataPath for DataFile {
     fn new(client: HttpClient, path: &str) -> Self {
         DataFile {
             client: client,
-            path: parse_data_uri(path).to_string(),
+            path: DataUri::from(path).to_path(),
+            progress: None,
+            #[cfg(any(feature = "gzip", feature = "zstd"))]
+            compression: None,
         }
     }
     #[doc(hidden)]
@@ -79,11 +192,39 @@ impl HasDataPath for DataFile {
     fn client(&self) -> &HttpClient {
         &self.client
     }
+    #[doc(hidden)]
+    fn set_progress_handler(&mut self, handler: Option<Arc<dyn ProgressHandler>>) {
+        self.progress = handler;
+    }
 }
 
 impl DataFile {
+    /// Builder method to report the progress of uploads and downloads of this file
+    ///
+    /// Progress is reported for in-memory bodies and local files passed to `put`, for `writer`,
+    /// and for all downloads. Bodies created with `Body::new` are uploaded without progress reports.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use algorithmia::Algorithmia;
+    /// # use algorithmia::data::Progress;
+    /// let client = Algorithmia::client("111112222233333444445555566")?;
+    /// client
+    ///     .file(".my/my_dir/model.bin")
+    ///     .progress(|p: &Progress| println!("{} bytes at {:.0} B/s", p.bytes, p.rate()))
+    ///     .download_to_path("/tmp/model.bin")?;
+    /// # Ok::<(), Box<std::error::Error>>(())
+    /// ```
+    pub fn progress<P: ProgressHandler + 'static>(&mut self, handler: P) -> &mut DataFile {
+        self.progress = Some(Arc::new(handler));
+        self
+    }
+
     /// Write to the Algorithmia Data API
     ///
+    /// Progress and compression only apply to in-memory bodies: upload local files
+    /// with `put_file`, and other readers of known length with `put_sized`.
+    ///
     /// # Examples
     /// ```no_run
     /// # use algorithmia::Algorithmia;
@@ -101,18 +242,243 @@ impl DataFile {
     where
         B: Into<Body>,
     {
-        let url = self.to_url()?;
-        self.client
-            .put(url)
-            .body(body)
-            .send()
-            .with_context(|| format!("request error writing file '{}'", self.to_data_uri()))
-            .and_then(process_http_response)
-            .with_context(|| format!("response error writing file '{}'", self.to_data_uri()))?;
+        self.put_raw(self.encode_body(body.into())?)
+    }
 
+    /// Upload the content of a local file, reporting progress and compressing it if configured
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use algorithmia::Algorithmia;
+    /// let client = Algorithmia::client("111112222233333444445555566")?;
+    /// client.file(".my/my_dir/file.jpg").put_file("/path/to/file.jpg")?;
+    /// # Ok::<(), Box<std::error::Error>>(())
+    /// ```
+    pub fn put_file<P: AsRef<Path>>(&self, local_path: P) -> Result<(), Error> {
+        let local_path = local_path.as_ref();
+        let file = File::open(local_path)
+            .with_context(|| format!("opening file for upload '{}'", local_path.display()))?;
+        let len = file
+            .metadata()
+            .with_context(|| format!("reading metadata of '{}'", local_path.display()))?
+            .len();
+        self.put_sized(file, len)
+    }
+
+    /// Write to the Algorithmia Data API, so that readers never observe a partial upload
+    ///
+    /// Uploads to a hidden temporary file next to this one (named `.<name>.tmp-<suffix>`),
+    /// checks that its size matches the content, then replaces this file with it.
+    /// If the upload or check fails, the temporary file is removed and this file is left unchanged.
+    /// The size can only be checked for in-memory bodies; streaming bodies are uploaded as they
+    /// are read and only checked to exist.
+    ///
+    /// Backends with a native move, such as `LocalBackend`, move the temporary file over this one
+    /// in a single step. The Data API has no move or copy operation, so there the verified
+    /// temporary file is streamed back through this client into a single upload replacing
+    /// this file, then deleted. That replacement is not atomic: while it runs, and if it fails
+    /// partway, readers may observe this file missing or incomplete. On failure the temporary file
+    /// is kept, and named in the error, so the uploaded content is not lost.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use algorithmia::Algorithmia;
+    /// let client = Algorithmia::client("111112222233333444445555566")?;
+    /// client.file(".my/my_dir/model.json").put_atomic(r#"{"version": 2}"#)?;
+    /// # Ok::<(), Box<std::error::Error>>(())
+    /// ```
+    pub fn put_atomic<B>(&self, body: B) -> Result<(), Error>
+    where
+        B: Into<Body>,
+    {
+        let backend = self.backend();
+        let body = self.encode_body(body.into())?;
+        let len = body.as_bytes().map(|bytes| bytes.len() as u64);
+
+        let data_uri = self.data_uri();
+        let name = match data_uri.file_name() {
+            Some(name) => name,
+            None => bail!("'{}' is not a valid path for a file", data_uri),
+        };
+        let temp_name = format!(
+            ".{}.tmp-{:x}-{:x}",
+            name,
+            Utc::now().timestamp_millis(),
+            ATOMIC_COUNTER.fetch_add(1, Ordering::Relaxed)
+        );
+        let temp_uri = match data_uri.parent() {
+            Some(parent) => parent.join(&temp_name),
+            None => bail!("'{}' is not a valid path for a file", data_uri),
+        };
+        let mut temp = DataFile::new(self.client.clone(), &temp_uri.to_string());
+        temp.progress = self.progress.clone();
+
+        let uploaded = temp.put_raw(body).and_then(|()| {
+            let size = match backend.head(&temp_uri)? {
+                Some(metadata) => metadata.size,
+                None => bail!("uploaded '{}', but it does not exist", temp_uri),
+            };
+            if len.is_some() && size != len {
+                bail!(
+                    "uploaded {} bytes to '{}', but it has a size of {}",
+                    len.unwrap_or_default(),
+                    temp_uri,
+                    size.map_or_else(|| "unknown".to_string(), |size| size.to_string())
+                );
+            }
+            Ok(size)
+        });
+        let size = match uploaded {
+            Ok(size) => size,
+            Err(err) => {
+                let _ = backend.delete_file(&temp_uri);
+                return Err(err);
+            }
+        };
+
+        if backend.supports_rename() {
+            let moved = backend.rename(&temp_uri, &data_uri);
+            if moved.is_err() {
+                let _ = backend.delete_file(&temp_uri);
+            }
+            return moved;
+        }
+
+        self.copy_from(&temp_uri, size).with_context(|| {
+            format!(
+                "error replacing '{}', which may be incomplete; the uploaded content remains in '{}'",
+                data_uri, temp_uri
+            )
+        })?;
+        // This file was replaced, so failing to clean up only leaves the hidden temporary file
+        let _ = backend.delete_file(&temp_uri);
+        Ok(())
+    }
+
+    /// Replace this file with the content of `from`, streamed through this client,
+    /// checking that `size` bytes were copied if it is known
+    fn copy_from(&self, from: &DataUri, size: Option<u64>) -> Result<(), Error> {
+        let backend = self.backend();
+        let mut data = backend.get(from, 0, None)?;
+        // Copy the content as stored, without compression or progress
+        let target = DataFile::new(self.client.clone(), &self.to_data_uri());
+        let mut writer = target.writer();
+        io::copy(&mut data, &mut writer)
+            .with_context(|| format!("error copying '{}' to '{}'", from, self.to_data_uri()))?;
+        writer.finish()?;
+
+        let copied = backend
+            .head(&self.data_uri())?
+            .and_then(|metadata| metadata.size);
+        if size.is_some() && copied != size {
+            bail!(
+                "copied {} bytes to '{}', but it has a size of {}",
+                size.unwrap_or_default(),
+                self.to_data_uri(),
+                copied.map_or_else(|| "unknown".to_string(), |size| size.to_string())
+            );
+        }
         Ok(())
     }
 
+    /// Upload the content of a reader, compressing it if configured
+    pub(crate) fn put_reader<R: Read + Send + 'static>(&self, reader: R) -> Result<(), Error> {
+        self.put_raw(self.encode_reader(reader, None)?)
+    }
+
+    /// Upload a body as the content of this file, without compression
+    pub(crate) fn put_raw(&self, body: Body) -> Result<(), Error> {
+        let body = match body.as_bytes().map(|bytes| bytes.len() as u64) {
+            Some(len) => match self.tracker(Direction::Upload, Some(len), 0) {
+                Some(tracker) => Body::sized(
+                    ProgressReader::new(BodyBytes { body, pos: 0 }, tracker),
+                    len,
+                ),
+                None => body,
+            },
+            None => body,
+        };
+
+        self.backend().put(&self.data_uri(), body)
+    }
+
+    /// Get the metadata of a file without downloading its content
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use algorithmia::Algorithmia;
+    /// let client = Algorithmia::client("111112222233333444445555566")?;
+    /// let metadata = client.file(".my/my_dir/model.bin").metadata()?;
+    /// println!("{} bytes, modified {:?}", metadata.size, metadata.last_modified);
+    /// # Ok::<(), Box<std::error::Error>>(())
+    /// ```
+    pub fn metadata(&self) -> Result<FileMetadata, Error> {
+        let metadata = self.head()?;
+        match metadata.data_type {
+            DataType::File => (),
+            DataType::Dir => {
+                bail!("expected API response with data type 'file', received 'directory'")
+            }
+        }
+
+        Ok(FileMetadata {
+            size: metadata.size.unwrap_or(0),
+            last_modified: metadata.last_modified,
+            content_type: metadata.content_type,
+            etag: metadata.etag,
+        })
+    }
+
+    /// Stream writes into the content of this file
+    ///
+    /// Bytes written to the returned `FileWriter` are uploaded as they are produced,
+    /// through a bounded buffer, so the full content is never held in memory.
+    /// Call `finish` to complete the upload and check that it succeeded.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use algorithmia::Algorithmia;
+    /// use std::io::Write;
+    ///
+    /// let client = Algorithmia::client("111112222233333444445555566")?;
+    /// let mut writer = client.file(".my/my_dir/numbers.txt").writer();
+    /// for i in 0..1_000_000 {
+    ///     writeln!(writer, "{}", i)?;
+    /// }
+    /// writer.finish()?;
+    /// # Ok::<(), Box<std::error::Error>>(())
+    /// ```
+    pub fn writer(&self) -> FileWriter {
+        FileWriter::new(self.clone())
+    }
+
+    /// Write a value to the Algorithmia Data API as JSON
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use algorithmia::Algorithmia;
+    /// # use std::collections::HashMap;
+    /// let client = Algorithmia::client("111112222233333444445555566")?;
+    /// let mut scores = HashMap::new();
+    /// scores.insert("accuracy", 0.97);
+    /// client.file(".my/my_dir/scores.json").put_json(&scores)?;
+    /// # Ok::<(), Box<std::error::Error>>(())
+    /// ```
+    pub fn put_json<T: Serialize + ?Sized>(&self, value: &T) -> Result<(), Error> {
+        let json = serde_json::to_vec(value).with_context(|| {
+            format!("JSON encoding error writing file '{}'", self.to_data_uri())
+        })?;
+        self.put(json)
+    }
+
+    /// Write a value to the Algorithmia Data API as pretty-printed JSON
+    pub fn put_json_pretty<T: Serialize + ?Sized>(&self, value: &T) -> Result<(), Error> {
+        let json = serde_json::to_vec_pretty(value).with_context(|| {
+            format!("JSON encoding error writing file '{}'", self.to_data_uri())
+        })?;
+        self.put(json)
+    }
+
     /// Get a file from the Algorithmia Data API
     ///
     /// # Examples
@@ -126,27 +492,266 @@ impl DataFile {
     /// # Ok::<_, Box<std::error::Error>>(())
     /// ```
     pub fn get(&self) -> Result<FileData, Error> {
-        let url = self.to_url()?;
-        let req = self.client.get(url);
-        let res = req
-            .send()
-            .with_context(|| format!("request error downloading file '{}'", self.to_data_uri()))
-            .and_then(process_http_response)
-            .with_context(|| format!("response error downloading file '{}'", self.to_data_uri()))?;
-
-        let metadata = parse_headers(res.headers())?;
+        let data = self
+            .request_file(0, None)
+            .map(|data| self.track_download(data))?;
+        self.decode_data(data)
+    }
+
+    /// Get a file from the Algorithmia Data API and decode its JSON content
+    ///
+    /// The content is deserialized as it is downloaded, without buffering the entire file.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use algorithmia::Algorithmia;
+    /// # use std::collections::HashMap;
+    /// let client = Algorithmia::client("111112222233333444445555566")?;
+    /// let config: HashMap<String, f64> = client.file(".my/my_dir/config.json").get_json()?;
+    /// # Ok::<_, Box<std::error::Error>>(())
+    /// ```
+    pub fn get_json<T: DeserializeOwned>(&self) -> Result<T, Error> {
+        let data = self.get()?;
+        serde_json::from_reader(BufReader::new(data))
+            .with_context(|| format!("JSON decoding error reading file '{}'", self.to_data_uri()))
+    }
+
+    /// Get a range of bytes from a file in the Algorithmia Data API
+    ///
+    /// Uses an HTTP `Range` request, so only the requested bytes are transferred.
+    /// The `size` of the returned `FileData` is the length of the range.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use algorithmia::Algorithmia;
+    /// let client = Algorithmia::client("111112222233333444445555566")?;
+    /// let my_file = client.file(".my/my_dir/model.bin");
+    ///
+    /// let header = my_file.get_range(0..512)?.into_bytes()?;
+    /// let tail = my_file.get_range(1024..)?.into_bytes()?;
+    /// # Ok::<_, Box<std::error::Error>>(())
+    /// ```
+    pub fn get_range<R: RangeBounds<u64>>(&self, range: R) -> Result<FileData, Error> {
+        let start = match range.start_bound() {
+            Bound::Included(&s) => s,
+            Bound::Excluded(&s) => match s.checked_add(1) {
+                Some(start) => start,
+                None => bail!("invalid byte range starting after {}", s),
+            },
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&e) => Some(e),
+            Bound::Excluded(&0) => bail!("invalid empty byte range"),
+            Bound::Excluded(&e) => Some(e - 1),
+            Bound::Unbounded => None,
+        };
+        if let Some(end) = end {
+            if end < start {
+                bail!("invalid byte range {}..={}", start, end);
+            }
+        }
+        self.request_file(start, end)
+            .map(|data| self.track_download(data))
+    }
+
+    /// Download a file to a local path, resuming any partially downloaded content
+    ///
+    /// While downloading, the version of the file (its ETag, or else its last modified
+    /// timestamp and size) is recorded next to the local file, in `<local_path>.resume`.
+    /// If `local_path` already exists and that record matches the current version,
+    /// only the bytes beyond its current length are requested; otherwise the local file
+    /// is truncated and downloaded from the start. Interrupted transfers are retried
+    /// from where they left off. Returns the size of the completed local file.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use algorithmia::Algorithmia;
+    /// let client = Algorithmia::client("111112222233333444445555566")?;
+    /// let my_file = client.file(".my/my_dir/model.bin");
+    ///
+    /// let size = my_file.download_to_path("/tmp/model.bin")?;
+    /// # Ok::<_, Box<std::error::Error>>(())
+    /// ```
+    pub fn download_to_path<P: AsRef<Path>>(&self, local_path: P) -> Result<u64, Error> {
+        let local_path = local_path.as_ref();
+        let metadata = self.head()?;
+        let version = resume_version(&metadata);
+        let mut resume_path = local_path.as_os_str().to_owned();
+        resume_path.push(".resume");
+        let resume_path = PathBuf::from(resume_path);
+
+        let mut local = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(false)
+            .open(local_path)
+            .with_context(|| format!("opening '{}' for download", local_path.display()))?;
+        let len = local
+            .metadata()
+            .with_context(|| format!("reading metadata of '{}'", local_path.display()))?
+            .len();
+        let resumable = version.is_some()
+            && fs::read_to_string(&resume_path).ok() == version
+            && metadata.size.is_some_and(|size| len <= size);
+        let written = if resumable {
+            len
+        } else {
+            // The local file is not known to be a prefix of this version, so start over
+            local
+                .set_len(0)
+                .with_context(|| format!("truncating '{}'", local_path.display()))?;
+            0
+        };
+        match &version {
+            Some(version) => fs::write(&resume_path, version)
+                .with_context(|| format!("writing '{}'", resume_path.display()))?,
+            None => {
+                let _ = fs::remove_file(&resume_path);
+            }
+        }
+
+        local
+            .seek(SeekFrom::Start(written))
+            .with_context(|| format!("seeking in '{}'", local_path.display()))?;
+        let size = self.download_into(&mut local, local_path, &metadata, written)?;
+        let _ = fs::remove_file(&resume_path);
+        Ok(size)
+    }
+
+    /// Download the content after the first `written` bytes into `local`, retrying interrupted transfers
+    ///
+    /// Errors writing to `local` are returned without retrying.
+    pub(crate) fn download_into(
+        &self,
+        local: &mut File,
+        local_path: &Path,
+        metadata: &ObjectMetadata,
+        mut written: u64,
+    ) -> Result<u64, Error> {
+        let total = metadata.size;
+        let mut tracker = self.tracker(Direction::Download, total, written);
+        let mut attempt = 0;
+        let mut buf = vec![0; 64 * 1024];
+        while total != Some(written) {
+            let res = match self.request_file(written, None) {
+                Ok(mut data) => {
+                    if let (Some(expected), Some(etag)) = (&metadata.etag, &data.etag) {
+                        if expected != etag {
+                            bail!("file '{}' changed during download", self.to_data_uri());
+                        }
+                    }
+                    loop {
+                        let n = match data.read(&mut buf) {
+                            Ok(0) => break Ok(()),
+                            Ok(n) => n,
+                            Err(err) => {
+                                break Err(err).with_context(|| {
+                                    format!("downloading file '{}'", self.to_data_uri())
+                                })
+                            }
+                        };
+                        local
+                            .write_all(&buf[..n])
+                            .with_context(|| format!("writing to '{}'", local_path.display()))?;
+                        written += n as u64;
+                        if let Some(tracker) = &mut tracker {
+                            tracker.advance(n as u64);
+                        }
+                    }
+                }
+                Err(err) => Err(err),
+            };
+
+            let complete = match total {
+                Some(total) => written >= total,
+                None => true,
+            };
+            match res {
+                Ok(()) if complete => break,
+                Err(err) if !err.is_transient() || attempt >= DOWNLOAD_RETRIES => return Err(err),
+                Ok(()) if attempt >= DOWNLOAD_RETRIES => bail!(
+                    "download of '{}' ended after {} of {} bytes",
+                    self.to_data_uri(),
+                    written,
+                    total.unwrap_or(0)
+                ),
+                _ => {
+                    attempt += 1;
+                    thread::sleep(Duration::from_millis(500 * u64::from(attempt)));
+                }
+            }
+        }
+
+        if let Some(tracker) = &mut tracker {
+            tracker.finish();
+        }
+        Ok(written)
+    }
+
+    /// Get the path of a local copy of this file, downloading it only if it changed
+    ///
+    /// Requires a cache configured with `Algorithmia::cache`. A `HEAD` request checks
+    /// the size and last modified timestamp of the cached copy before it is reused;
+    /// otherwise the file is downloaded into the cache. The cached copy holds the stored bytes
+    /// of the file (i.e. without transparent decompression).
+    ///
+    /// The returned path remains valid until the file is evicted from the cache,
+    /// so copy or open the file before caching other files if the cache is small.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use algorithmia::Algorithmia;
+    /// # use algorithmia::data::DataCache;
+    /// let mut client = Algorithmia::client("111112222233333444445555566")?;
+    /// client.cache(DataCache::new("/tmp/algorithmia-cache", 1024 * 1024 * 1024)?);
+    ///
+    /// let model = std::fs::read(client.file(".my/my_dir/model.bin").get_cached()?)?;
+    /// # Ok::<_, Box<std::error::Error>>(())
+    /// ```
+    pub fn get_cached(&self) -> Result<PathBuf, Error> {
+        let cache = match self.client.cache() {
+            Some(cache) => cache,
+            None => bail!(
+                "no data cache configured for caching file '{}'",
+                self.to_data_uri()
+            ),
+        };
+        let data_uri = self.to_data_uri();
+        let metadata = self.metadata()?;
+        match cache.lookup(&data_uri, &metadata) {
+            Some(path) => Ok(path),
+            None => cache.insert(&data_uri, &metadata, |path| self.download_to_path(path)),
+        }
+    }
+
+    /// Open a file for random access without downloading it
+    ///
+    /// The returned reader implements `Seek` by issuing range requests on demand,
+    /// buffering up to its configured read-ahead size with each request.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use algorithmia::Algorithmia;
+    /// use std::io::{Read, Seek, SeekFrom};
+    ///
+    /// let client = Algorithmia::client("111112222233333444445555566")?;
+    /// let mut reader = client.file(".my/my_dir/archive.zip").open_seekable()?;
+    ///
+    /// let mut footer = [0; 22];
+    /// reader.seek(SeekFrom::End(-22))?;
+    /// reader.read_exact(&mut footer)?;
+    /// # Ok::<_, Box<std::error::Error>>(())
+    /// ```
+    pub fn open_seekable(&self) -> Result<SeekableFile, Error> {
+        let metadata = self.head()?;
         match metadata.data_type {
             DataType::File => (),
             DataType::Dir => {
                 bail!("expected API response with data type 'file', received 'directory'")
             }
         }
-
-        Ok(FileData {
-            size: metadata.content_length.unwrap_or(0),
-            last_modified: metadata.last_modified.unwrap_or_else(algo_epoch),
-            data: Box::new(res),
-        })
+        Ok(SeekableFile::new(self.clone(), metadata.size))
     }
 
     /// Delete a file from from the Algorithmia Data API
@@ -164,13 +769,217 @@ impl DataFile {
     /// # Ok::<(), Box<std::error::Error>>(())
     /// ```
     pub fn delete(&self) -> Result<(), Error> {
-        let url = self.to_url()?;
-        let req = self.client.delete(url);
-        req.send()
-            .with_context(|| format!("request error deleting file '{}'", self.to_data_uri()))
-            .and_then(process_http_response)
-            .with_context(|| format!("response error deleting file '{}'", self.to_data_uri()))?;
+        self.backend().delete_file(&self.data_uri())
+    }
 
-        Ok(())
+    pub(crate) fn head(&self) -> Result<ObjectMetadata, Error> {
+        match self.backend().head(&self.data_uri())? {
+            Some(metadata) => Ok(metadata),
+            None => bail!("file '{}' does not exist", self.to_data_uri()),
+        }
+    }
+
+    pub(crate) fn tracker(
+        &self,
+        direction: Direction,
+        total: Option<u64>,
+        resumed_from: u64,
+    ) -> Option<ProgressTracker> {
+        self.progress.as_ref().map(|handler| {
+            ProgressTracker::new(
+                handler.clone(),
+                self.to_data_uri(),
+                direction,
+                total,
+                resumed_from,
+            )
+        })
+    }
+
+    pub(crate) fn track_download(&self, data: FileData) -> FileData {
+        match self.tracker(Direction::Download, Some(data.size), 0) {
+            Some(tracker) => {
+                data.map_reader(|reader| Box::new(ProgressReader::new(reader, tracker)))
+            }
+            None => data,
+        }
+    }
+
+    /// Upload `len` bytes from a reader, reporting progress and compressing it if configured
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use algorithmia::Algorithmia;
+    /// # use std::io::Cursor;
+    /// let client = Algorithmia::client("111112222233333444445555566")?;
+    /// let content = vec![0u8; 1024];
+    /// client.file(".my/my_dir/zeros.bin").put_sized(Cursor::new(content), 1024)?;
+    /// # Ok::<(), Box<std::error::Error>>(())
+    /// ```
+    pub fn put_sized<R: Read + Send + 'static>(&self, reader: R, len: u64) -> Result<(), Error> {
+        let body = match self.tracker(Direction::Upload, Some(len), 0) {
+            Some(tracker) => self.encode_reader(ProgressReader::new(reader, tracker), Some(len))?,
+            None => self.encode_reader(reader, Some(len))?,
+        };
+        self.put_raw(body)
+    }
+
+    pub(crate) fn request_file(&self, start: u64, end: Option<u64>) -> Result<FileData, Error> {
+        self.backend().get(&self.data_uri(), start, end)
+    }
+}
+
+/// Without a compression codec enabled, content is uploaded and downloaded as is
+#[cfg(not(any(feature = "gzip", feature = "zstd")))]
+impl DataFile {
+    pub(crate) fn encode_body(&self, body: Body) -> Result<Body, Error> {
+        Ok(body)
+    }
+
+    pub(crate) fn encode_reader<R>(&self, reader: R, len: Option<u64>) -> Result<Body, Error>
+    where
+        R: Read + Send + 'static,
+    {
+        match len {
+            Some(len) => Ok(Body::sized(reader, len)),
+            None => Ok(Body::new(reader)),
+        }
+    }
+
+    pub(crate) fn decode_data(&self, data: FileData) -> Result<FileData, Error> {
+        Ok(data)
+    }
+}
+
+/// Version of a file recorded to validate resumed downloads, if it can be identified
+fn resume_version(metadata: &ObjectMetadata) -> Option<String> {
+    let size = metadata.size?;
+    match (&metadata.etag, metadata.last_modified) {
+        (Some(etag), _) => Some(format!("{} etag {}", size, etag)),
+        (None, Some(last_modified)) => Some(format!(
+            "{} last-modified {}",
+            size,
+            last_modified.to_rfc3339()
+        )),
+        (None, None) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FileData;
+    use crate::data::test_support::LocalTempDir;
+    use crate::data::{MemoryBackend, Progress};
+    use crate::Algorithmia;
+    use std::ops::Bound;
+    use std::sync::{Arc, Mutex};
+
+    fn mock_client() -> Algorithmia {
+        Algorithmia::client("").unwrap()
+    }
+
+    #[test]
+    fn test_invalid_range() {
+        let file = mock_client().file("data://.my/foo/bar.txt");
+        assert!(file
+            .get_range((Bound::Included(10), Bound::Excluded(5)))
+            .is_err());
+        assert!(file
+            .get_range((Bound::Included(10), Bound::Included(9)))
+            .is_err());
+        assert!(file.get_range(..0).is_err());
+        assert!(file
+            .get_range((Bound::Excluded(u64::MAX), Bound::Unbounded))
+            .is_err());
+    }
+
+    #[test]
+    fn test_put_progress() {
+        let mut client = mock_client();
+        client.backend(MemoryBackend::new());
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink = reports.clone();
+        let mut file = client.file("data://.my/bar.txt");
+        file.progress(move |p: &Progress| sink.lock().unwrap().push(p.bytes));
+
+        file.put("hello world").unwrap();
+        assert_eq!(reports.lock().unwrap().last(), Some(&11));
+
+        let tmp = LocalTempDir::new("put");
+        let local_path = tmp.join("local.txt");
+        std::fs::write(&local_path, "hello local world").unwrap();
+        file.put_file(&local_path).unwrap();
+        assert_eq!(reports.lock().unwrap().last(), Some(&17));
+        assert_eq!(
+            file.get().unwrap().into_string().unwrap(),
+            "hello local world"
+        );
+    }
+
+    #[test]
+    fn test_download_resume() {
+        let mut client = mock_client();
+        client.backend(MemoryBackend::new());
+        let file = client.file("data://.my/bar.txt");
+        file.put("0123456789").unwrap();
+        let tmp = LocalTempDir::new("download");
+        let local_path = tmp.join("bar.txt");
+        let resume_path = tmp.join("bar.txt.resume");
+
+        // An unrelated local file is replaced, not appended to
+        std::fs::write(&local_path, "xyz").unwrap();
+        assert_eq!(file.download_to_path(&local_path).unwrap(), 10);
+        assert_eq!(std::fs::read_to_string(&local_path).unwrap(), "0123456789");
+        assert!(!resume_path.exists());
+
+        // A partial download of the same version is resumed after its local bytes
+        std::fs::write(&local_path, "abc").unwrap();
+        let version = super::resume_version(&file.head().unwrap()).unwrap();
+        std::fs::write(&resume_path, &version).unwrap();
+        assert_eq!(file.download_to_path(&local_path).unwrap(), 10);
+        assert_eq!(std::fs::read_to_string(&local_path).unwrap(), "abc3456789");
+        assert!(!resume_path.exists());
+    }
+
+    #[test]
+    fn test_transient_errors() {
+        use crate::error::ResultExt;
+        use std::io;
+        let io_err = |kind| Err::<(), _>(io::Error::new(kind, "failed")).context("context");
+        assert!(io_err(io::ErrorKind::ConnectionReset)
+            .unwrap_err()
+            .is_transient());
+        assert!(!io_err(io::ErrorKind::PermissionDenied)
+            .unwrap_err()
+            .is_transient());
+    }
+
+    #[test]
+    fn test_range_bounds() {
+        let mut client = mock_client();
+        client.backend(MemoryBackend::new());
+        let file = client.file("data://.my/bar.txt");
+        file.put("0123456789").unwrap();
+
+        let read = |data: FileData| data.into_string().unwrap();
+        assert_eq!(read(file.get_range(2..5).unwrap()), "234");
+        assert_eq!(read(file.get_range(2..=5).unwrap()), "2345");
+        assert_eq!(read(file.get_range(7..).unwrap()), "789");
+        assert_eq!(read(file.get_range(..3).unwrap()), "012");
+        assert_eq!(read(file.get_range(..).unwrap()), "0123456789");
+        assert_eq!(
+            read(
+                file.get_range((Bound::Excluded(2), Bound::Included(4)))
+                    .unwrap()
+            ),
+            "34"
+        );
+        assert_eq!(
+            read(
+                file.get_range((Bound::Excluded(6), Bound::Unbounded))
+                    .unwrap()
+            ),
+            "789"
+        );
     }
 }
@@ -0,0 +1,457 @@
+//! Glob matching across Algorithmia data paths
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use algorithmia::Algorithmia;
+//! use algorithmia::data::{DataItem, HasDataPath};
+//!
+//! let client = Algorithmia::client("111112222233333444445555566")?;
+//! for item in client.glob("data://.my/runs/2026-*/**/*.csv") {
+//!     if let DataItem::File(f) = item? {
+//!         println!("{} ({} bytes)", f.to_data_uri(), f.size);
+//!     }
+//! }
+//! # Ok::<(), Box<std::error::Error>>(())
+//! ```
+
+use crate::client::HttpClient;
+use crate::data::{DataDir, DataItem, DataObject, DataUri, HasDataPath};
+use crate::error::Error;
+use std::collections::{HashSet, VecDeque};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    /// `**` matches zero or more directories
+    AnyDirs,
+    /// Matches a single file or directory name
+    Pattern(String),
+}
+
+/// Iterator over the data items matching a glob pattern
+///
+/// Created with [`Algorithmia::glob`](../struct.Algorithmia.html#method.glob).
+/// Only directories that the pattern can match are listed, and each item is yielded once,
+/// even if it matches through several `**` segments.
+pub struct Glob {
+    segments: Vec<Segment>,
+    /// Directories still to be listed, with the index of the segment their entries must match
+    pending: Vec<(DataDir, usize)>,
+    /// Whether several `**` segments can expand to the same paths, so results must be deduplicated
+    dedupe: bool,
+    /// Data URIs and segment indexes of directories already queued for listing, if deduplicating
+    queued: HashSet<(String, usize)>,
+    matched: VecDeque<DataItem>,
+    /// Data URIs of items already matched, if deduplicating
+    yielded: HashSet<String>,
+    literal: Option<DataObject>,
+}
+
+impl Glob {
+    pub(crate) fn new(client: HttpClient, pattern: &str) -> Glob {
+        let uri = DataUri::from(pattern);
+        let parts: Vec<&str> = uri.segments().iter().map(String::as_str).collect();
+        Glob::build(client, uri.truncated(0), &parts)
+    }
+
+    /// Match a pattern relative to `root`, whose path is taken literally
+    pub(crate) fn under(client: HttpClient, root: &DataUri, pattern: &str) -> Glob {
+        let parts: Vec<&str> = pattern
+            .split('/')
+            .filter(|p| !p.is_empty() && *p != ".")
+            .collect();
+        Glob::build(client, root.clone(), &parts)
+    }
+
+    fn build(client: HttpClient, mut base: DataUri, parts: &[&str]) -> Glob {
+        // Leading segments without wildcards are appended to the base instead of matched.
+        // `join_child` rejects `.` and `..`, which are left to never match a listed name.
+        let mut literal_len = 0;
+        for part in parts.iter().take_while(|p| !has_wildcard(p)) {
+            match base.join_child(&unescape(part)) {
+                Ok(uri) => base = uri,
+                Err(_) => break,
+            }
+            literal_len += 1;
+        }
+        let base_uri = base.to_string();
+
+        let mut segments: Vec<Segment> = Vec::new();
+        for part in &parts[literal_len..] {
+            let segment = match *part {
+                "**" => Segment::AnyDirs,
+                p => Segment::Pattern(p.to_string()),
+            };
+            // Consecutive `**` are equivalent to one and would yield duplicates
+            if !(segment == Segment::AnyDirs && segments.last() == Some(&Segment::AnyDirs)) {
+                segments.push(segment);
+            }
+        }
+        // With at most one `**`, the number of directories it spans is fixed by the depth
+        // of a path, so every directory and item is reached exactly once
+        let dedupe = segments.iter().filter(|s| **s == Segment::AnyDirs).count() > 1;
+
+        if segments.is_empty() {
+            Glob {
+                segments,
+                pending: Vec::new(),
+                dedupe,
+                queued: HashSet::new(),
+                matched: VecDeque::new(),
+                yielded: HashSet::new(),
+                literal: Some(DataObject::new(client, &base_uri)),
+            }
+        } else {
+            Glob {
+                segments,
+                pending: vec![(DataDir::new(client, &base_uri), 0)],
+                dedupe,
+                queued: HashSet::new(),
+                matched: VecDeque::new(),
+                yielded: HashSet::new(),
+                literal: None,
+            }
+        }
+    }
+
+    fn visit(&mut self, item: DataItem, idx: usize) {
+        if idx == self.segments.len() {
+            self.push_match(item);
+            return;
+        }
+
+        match &self.segments[idx] {
+            Segment::AnyDirs => {
+                if let DataItem::Dir(d) = &item {
+                    self.push_pending((**d).clone(), idx);
+                }
+                self.visit(item, idx + 1);
+            }
+            Segment::Pattern(pattern) => {
+                let name = item_name(&item);
+                if !matches_segment(pattern, &name) {
+                    return;
+                }
+                match item {
+                    _ if idx + 1 == self.segments.len() => self.push_match(item),
+                    DataItem::Dir(d) => self.push_pending((*d).clone(), idx + 1),
+                    DataItem::File(_) => (),
+                }
+            }
+        }
+    }
+
+    fn push_pending(&mut self, dir: DataDir, idx: usize) {
+        if !self.dedupe || self.queued.insert((dir.to_data_uri(), idx)) {
+            self.pending.push((dir, idx));
+        }
+    }
+
+    fn push_match(&mut self, item: DataItem) {
+        if self.dedupe {
+            let data_uri = match &item {
+                DataItem::File(f) => f.to_data_uri(),
+                DataItem::Dir(d) => d.to_data_uri(),
+            };
+            if !self.yielded.insert(data_uri) {
+                return;
+            }
+        }
+        self.matched.push_back(item);
+    }
+}
+
+impl Iterator for Glob {
+    type Item = Result<DataItem, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(object) = self.literal.take() {
+            return object.try_into_type().transpose();
+        }
+
+        loop {
+            if let Some(item) = self.matched.pop_front() {
+                return Some(Ok(item));
+            }
+
+            let (dir, idx) = self.pending.pop()?;
+            let entries: Result<Vec<DataItem>, Error> = dir.list().collect();
+            match entries {
+                Ok(entries) => {
+                    for entry in entries {
+                        self.visit(entry, idx);
+                    }
+                }
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+}
+
+fn item_name(item: &DataItem) -> String {
+    let name = match item {
+        DataItem::File(f) => f.basename(),
+        DataItem::Dir(d) => d.basename(),
+    };
+    name.unwrap_or_default()
+}
+
+fn has_wildcard(segment: &str) -> bool {
+    let mut chars = segment.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '*' | '?' | '[' => return true,
+            _ => (),
+        }
+    }
+    false
+}
+
+/// Remove the `\` escapes from a segment without wildcards
+fn unescape(segment: &str) -> String {
+    let mut unescaped = String::with_capacity(segment.len());
+    let mut chars = segment.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.push(chars.next().unwrap_or('\\')),
+            c => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+/// Match a single path segment against a pattern of `*`, `?` and `[...]` character classes
+///
+/// A `\` matches the following character literally, e.g. `\*` matches only `*`.
+pub(crate) fn matches_segment(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    matches_from(&pattern, &name)
+}
+
+fn matches_from(pattern: &[char], name: &[char]) -> bool {
+    let (mut p, mut n) = (0, 0);
+    // Pattern position after the last `*`, and the name position that `*` matched up to
+    let mut star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                p += 1;
+                star = Some((p, n));
+                continue;
+            }
+            Some(_) => {
+                if let Some(len) = match_one(&pattern[p..], name[n]) {
+                    p += len;
+                    n += 1;
+                    continue;
+                }
+            }
+            None => (),
+        }
+        // Let the last `*` match one more character, or fail if there is none
+        match star {
+            Some((star_p, star_n)) => {
+                p = star_p;
+                n = star_n + 1;
+                star = Some((star_p, n));
+            }
+            None => return false,
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Length of the `?`, `[...]` class, or literal at the start of `pattern`, if it matches `c`
+fn match_one(pattern: &[char], c: char) -> Option<usize> {
+    match pattern[0] {
+        '\\' => match pattern.get(1) {
+            Some(&escaped) if escaped == c => Some(2),
+            Some(_) => None,
+            // A trailing backslash is matched literally
+            None if c == '\\' => Some(1),
+            None => None,
+        },
+        '?' => Some(1),
+        '[' => match parse_class(&pattern[1..]) {
+            Some((class, rest)) if class.matches(c) => Some(pattern.len() - rest.len()),
+            Some(_) => None,
+            // An unterminated class is matched literally
+            None if c == '[' => Some(1),
+            None => None,
+        },
+        literal if literal == c => Some(1),
+        _ => None,
+    }
+}
+
+struct CharClass {
+    negated: bool,
+    ranges: Vec<(char, char)>,
+}
+
+impl CharClass {
+    fn matches(&self, c: char) -> bool {
+        self.ranges.iter().any(|&(lo, hi)| lo <= c && c <= hi) != self.negated
+    }
+}
+
+/// Parse the body of a `[...]` class, returning it and the remaining pattern
+fn parse_class(pattern: &[char]) -> Option<(CharClass, &[char])> {
+    let negated = matches!(pattern.first(), Some('!') | Some('^'));
+    let mut i = if negated { 1 } else { 0 };
+    let mut ranges = Vec::new();
+    let mut first = true;
+    while i < pattern.len() {
+        let c = pattern[i];
+        // A `]` immediately after the opening bracket is a literal
+        if c == ']' && !first {
+            return Some((CharClass { negated, ranges }, &pattern[i + 1..]));
+        }
+        if i + 2 < pattern.len() && pattern[i + 1] == '-' && pattern[i + 2] != ']' {
+            ranges.push((c, pattern[i + 2]));
+            i += 3;
+        } else {
+            ranges.push((c, c));
+            i += 1;
+        }
+        first = false;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{DataAcl, MemoryBackend};
+    use crate::Algorithmia;
+
+    fn mock_glob(pattern: &str) -> Glob {
+        let client = Algorithmia::client("").unwrap();
+        client.glob(pattern)
+    }
+
+    #[test]
+    fn test_matches_segment() {
+        assert!(matches_segment("*.csv", "runs.csv"));
+        assert!(matches_segment("*.csv", ".csv"));
+        assert!(!matches_segment("*.csv", "runs.csv.gz"));
+        assert!(matches_segment("2026-*", "2026-01-05"));
+        assert!(matches_segment("file?.txt", "file1.txt"));
+        assert!(!matches_segment("file?.txt", "file10.txt"));
+        assert!(matches_segment("[abc]*", "beta"));
+        assert!(!matches_segment("[abc]*", "delta"));
+        assert!(matches_segment("v[0-9].[0-9]", "v1.2"));
+        assert!(matches_segment("[!.]*", "visible"));
+        assert!(!matches_segment("[!.]*", ".hidden"));
+        assert!(matches_segment("[]]", "]"));
+        assert!(matches_segment("a[b", "a[b"));
+        assert!(matches_segment("*", ""));
+        assert!(matches_segment("*a*b", "xaxxb"));
+        assert!(!matches_segment("*a*b", "xaxxbc"));
+        assert!(matches_segment("a*[0-9]?", "abc12"));
+    }
+
+    #[test]
+    fn test_matches_escaped() {
+        assert!(matches_segment(r"\*.csv", "*.csv"));
+        assert!(!matches_segment(r"\*.csv", "runs.csv"));
+        assert!(matches_segment(r"a\?*", "a?b"));
+        assert!(!matches_segment(r"a\?*", "ab"));
+        assert!(matches_segment(r"\[x]", "[x]"));
+        assert!(matches_segment(r"a\", r"a\"));
+        assert!(!has_wildcard(r"run\*"));
+        assert!(has_wildcard(r"run\**"));
+        assert_eq!(unescape(r"run\[1\]"), "run[1]");
+    }
+
+    #[test]
+    fn test_glob_under_literal_root() {
+        let mut client = Algorithmia::client("").unwrap();
+        client.backend(MemoryBackend::new());
+        for dir in &[".my/[runs]", ".my/runs", ".my/[runs]/a"] {
+            client.dir(*dir).create(DataAcl::default()).unwrap();
+        }
+        client.file(".my/[runs]/a/x.txt").put("x").unwrap();
+        client.file(".my/runs/y.txt").put("y").unwrap();
+
+        let root: DataUri = "data://.my/[runs]".parse().unwrap();
+        let glob = Glob::under(client.http_client.clone(), &root, "**/*");
+        assert!(!glob.dedupe);
+        let matches: Vec<String> = glob
+            .map(|item| match item.unwrap() {
+                DataItem::File(f) => f.to_data_uri(),
+                DataItem::Dir(d) => d.to_data_uri(),
+            })
+            .collect();
+        assert_eq!(
+            matches,
+            vec!["data://.my/[runs]/a", "data://.my/[runs]/a/x.txt"]
+        );
+
+        let escaped: Vec<_> = client.glob(r"data://.my/\[runs\]/*/*.txt").collect();
+        assert_eq!(escaped.len(), 1);
+    }
+
+    #[test]
+    fn test_matches_segment_backtracking() {
+        let name = "a".repeat(200);
+        assert!(!matches_segment("*a*a*a*a*a*a*a*a*b", &name));
+        assert!(matches_segment("*a*a*a*a*a*a*a*a*", &name));
+    }
+
+    #[test]
+    fn test_each_match_yielded_once() {
+        let mut client = Algorithmia::client("").unwrap();
+        client.backend(MemoryBackend::new());
+        for dir in &[".my/runs", ".my/runs/a", ".my/runs/a/a"] {
+            client.dir(*dir).create(DataAcl::default()).unwrap();
+        }
+        client.file(".my/runs/a/a/x.txt").put("x").unwrap();
+
+        let matches: Vec<String> = client
+            .glob("data://.my/runs/**/a/**/*.txt")
+            .map(|item| match item.unwrap() {
+                DataItem::File(f) => f.to_data_uri(),
+                DataItem::Dir(d) => d.to_data_uri(),
+            })
+            .collect();
+        assert_eq!(matches, vec!["data://.my/runs/a/a/x.txt"]);
+    }
+
+    #[test]
+    fn test_literal_prefix() {
+        let glob = mock_glob("data://.my/runs/2026-*/**/*.csv");
+        assert_eq!(glob.pending[0].0.to_data_uri(), "data://.my/runs");
+        assert_eq!(
+            glob.segments,
+            vec![
+                Segment::Pattern("2026-*".into()),
+                Segment::AnyDirs,
+                Segment::Pattern("*.csv".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collapse_any_dirs() {
+        let glob = mock_glob(".my/**/**/*.txt");
+        assert_eq!(
+            glob.segments,
+            vec![Segment::AnyDirs, Segment::Pattern("*.txt".into())]
+        );
+    }
+
+    #[test]
+    fn test_pattern_without_wildcards() {
+        let glob = mock_glob("data://.my/foo/bar.txt");
+        assert!(glob.pending.is_empty());
+        assert_eq!(
+            glob.literal.unwrap().to_data_uri(),
+            "data://.my/foo/bar.txt"
+        );
+    }
+}
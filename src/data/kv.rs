@@ -0,0 +1,301 @@
+//! Key-value store of serde values in a data directory
+//!
+//! Each key is stored as a JSON file in the directory, named after the key with
+//! characters other than ASCII letters, digits, `-`, `_`, and non-leading `.` percent-escaped.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use algorithmia::Algorithmia;
+//! use algorithmia::data::kv::KvStore;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Deserialize, Serialize)]
+//! struct Checkpoint {
+//!     offset: u64,
+//! }
+//!
+//! let client = Algorithmia::client("111112222233333444445555566")?;
+//! let state = KvStore::new(client.dir(".my/state"));
+//!
+//! let checkpoint: Option<Checkpoint> = state.get("ingest/orders")?;
+//! let offset = checkpoint.map_or(0, |c| c.offset);
+//! state.put("ingest/orders", &Checkpoint { offset: offset + 100 })?;
+//! # Ok::<(), Box<std::error::Error>>(())
+//! ```
+
+use crate::data::{DataDir, DataFile, DataItem, FileData, HasDataPath, PutCondition};
+use crate::error::{Error, ResultExt};
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::io::BufReader;
+
+const VALUE_EXT: &str = ".json";
+
+/// Key-value store of JSON-serialized values, with one file per key in a `DataDir`
+///
+/// The directory must exist before values are written.
+#[derive(Clone)]
+pub struct KvStore {
+    dir: DataDir,
+}
+
+/// Value read from a `KvStore` with its version
+#[derive(Debug, Clone, PartialEq)]
+pub struct Versioned<T> {
+    /// Deserialized value
+    pub value: T,
+    /// Version of the value, for use with `put_if_unchanged`
+    pub version: Version,
+}
+
+/// Version of a value in a `KvStore`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Version {
+    /// Entity tag of the file storing the value, where the storage backend reports one
+    ETag(String),
+    /// Last modified timestamp of the file storing the value, otherwise
+    ///
+    /// Only as precise as the backend's timestamps: the Data API reports them to the second.
+    LastModified(DateTime<Utc>),
+    /// The storage backend reported neither, so the value cannot be updated with `put_if_unchanged`
+    Unknown,
+}
+
+impl Version {
+    fn of_data(data: &FileData) -> Version {
+        match data.etag {
+            Some(ref etag) => Version::ETag(etag.clone()),
+            None if data.last_modified_known => Version::LastModified(data.last_modified),
+            None => Version::Unknown,
+        }
+    }
+}
+
+impl KvStore {
+    /// Store values in the files of `dir`
+    pub fn new(dir: DataDir) -> KvStore {
+        KvStore { dir }
+    }
+
+    /// The directory values are stored in
+    pub fn dir(&self) -> &DataDir {
+        &self.dir
+    }
+
+    /// Get the value of a key, or `None` if it is not set
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, Error> {
+        Ok(self.get_versioned(key)?.map(|versioned| versioned.value))
+    }
+
+    /// Get the value of a key with its version, or `None` if it is not set
+    pub fn get_versioned<T: DeserializeOwned>(
+        &self,
+        key: &str,
+    ) -> Result<Option<Versioned<T>>, Error> {
+        let file = self.file(key)?;
+        let data = match file.get() {
+            Ok(data) => data,
+            // Only check whether the key is set when getting it fails
+            Err(err) => {
+                if file.exists()? {
+                    return Err(err);
+                }
+                return Ok(None);
+            }
+        };
+        let version = Version::of_data(&data);
+        let value = serde_json::from_reader(BufReader::new(data))
+            .with_context(|| format!("JSON decoding error reading key '{}'", key))?;
+        Ok(Some(Versioned { value, version }))
+    }
+
+    /// Set the value of a key
+    pub fn put<T: Serialize + ?Sized>(&self, key: &str, value: &T) -> Result<(), Error> {
+        self.file(key)?.put_json(value)
+    }
+
+    /// Remove a key, returning whether it was set
+    pub fn delete(&self, key: &str) -> Result<bool, Error> {
+        let file = self.file(key)?;
+        if !file.exists()? {
+            return Ok(false);
+        }
+        file.delete()?;
+        Ok(true)
+    }
+
+    /// All keys in the store, sorted
+    ///
+    /// Files in the directory that are not named like escaped keys are ignored.
+    pub fn keys(&self) -> Result<Vec<String>, Error> {
+        let mut listing = self.dir.listing();
+        listing.files_only();
+        let mut keys = Vec::new();
+        for item in listing {
+            if let DataItem::File(f) = item? {
+                let name = f.basename().unwrap_or_default();
+                if !name.ends_with(VALUE_EXT) {
+                    continue;
+                }
+                if let Some(key) = unescape_key(&name[..name.len() - VALUE_EXT.len()]) {
+                    keys.push(key);
+                }
+            }
+        }
+        keys.sort();
+        Ok(keys)
+    }
+
+    /// Set the value of a key if its version is still `version`
+    ///
+    /// Use `None` to only set a key that is not set. Returns whether the value was set,
+    /// or an error if the version is `Version::Unknown`.
+    ///
+    /// The version is checked by the storage backend as part of the write (on the Data API,
+    /// with an `If-Match`, `If-Unmodified-Since` or `If-None-Match` header), so of several
+    /// writers setting a key from the same version, only one succeeds. Versions that are
+    /// last modified timestamps (see `Version`) can miss updates within the same second.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use algorithmia::Algorithmia;
+    /// # use algorithmia::data::kv::KvStore;
+    /// let client = Algorithmia::client("111112222233333444445555566")?;
+    /// let counters = KvStore::new(client.dir(".my/counters"));
+    /// loop {
+    ///     let current = counters.get_versioned::<u64>("runs")?;
+    ///     let (count, version) = match current {
+    ///         Some(v) => (v.value, Some(v.version)),
+    ///         None => (0, None),
+    ///     };
+    ///     if counters.put_if_unchanged("runs", version.as_ref(), &(count + 1))? {
+    ///         break;
+    ///     }
+    /// }
+    /// # Ok::<(), Box<std::error::Error>>(())
+    /// ```
+    pub fn put_if_unchanged<T: Serialize + ?Sized>(
+        &self,
+        key: &str,
+        version: Option<&Version>,
+        value: &T,
+    ) -> Result<bool, Error> {
+        let condition = match version {
+            None => PutCondition::Absent,
+            Some(Version::ETag(etag)) => PutCondition::ETag(etag.clone()),
+            Some(Version::LastModified(time)) => PutCondition::LastModified(*time),
+            Some(Version::Unknown) => bail!(
+                "cannot set key '{}' if unchanged: the storage backend did not report its version",
+                key
+            ),
+        };
+        let file = self.file(key)?;
+        let json = serde_json::to_vec(value)
+            .with_context(|| format!("JSON encoding error writing key '{}'", key))?;
+        file.backend()
+            .put_if(&file.data_uri(), &condition, json.into())
+    }
+
+    fn file(&self, key: &str) -> Result<DataFile, Error> {
+        if key.is_empty() {
+            bail!("key-value store keys cannot be empty");
+        }
+        Ok(self.dir.child(&format!("{}{}", escape_key(key), VALUE_EXT)))
+    }
+}
+
+fn escape_key(key: &str) -> String {
+    let mut escaped = String::with_capacity(key.len());
+    for (i, byte) in key.bytes().enumerate() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' => escaped.push(byte as char),
+            // A leading dot would hide the file, or name `.` or `..`
+            b'.' if i > 0 => escaped.push('.'),
+            _ => escaped.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    escaped
+}
+
+fn unescape_key(name: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(name.len());
+    let mut rest = name.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = std::str::from_utf8(tail.get(..2)?).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{DataAcl, MemoryBackend};
+    use crate::Algorithmia;
+
+    #[test]
+    fn test_escape_key() {
+        assert_eq!(escape_key("simple-key_1"), "simple-key_1");
+        assert_eq!(escape_key("a/b c.txt"), "a%2Fb%20c.txt");
+        assert_eq!(escape_key(".."), "%2E.");
+        assert_eq!(escape_key("100%"), "100%25");
+        for key in &["a/b c.txt", "..", "100%", "ünïcode"] {
+            assert_eq!(unescape_key(&escape_key(key)).as_deref(), Some(*key));
+        }
+        assert_eq!(unescape_key("bad%2"), None);
+    }
+
+    #[test]
+    fn test_kv_store() {
+        let mut client = Algorithmia::client("").unwrap();
+        client.backend(MemoryBackend::new());
+        client.dir(".my/state").create(DataAcl::default()).unwrap();
+        client.file(".my/state/README").put("not a key").unwrap();
+        let kv = KvStore::new(client.dir(".my/state"));
+
+        assert_eq!(kv.get::<u32>("a/b").unwrap(), None);
+        kv.put("a/b", &1).unwrap();
+        kv.put("c", &vec!["x", "y"]).unwrap();
+        assert_eq!(kv.get::<u32>("a/b").unwrap(), Some(1));
+        assert_eq!(kv.keys().unwrap(), vec!["a/b", "c"]);
+        assert!(kv.get::<String>("a/b").is_err());
+        assert!(kv.put("", &0).is_err());
+
+        assert!(kv.delete("c").unwrap());
+        assert!(!kv.delete("c").unwrap());
+        assert_eq!(kv.keys().unwrap(), vec!["a/b"]);
+    }
+
+    #[test]
+    fn test_put_if_unchanged() {
+        let mut client = Algorithmia::client("").unwrap();
+        client.backend(MemoryBackend::new());
+        client.dir(".my/state").create(DataAcl::default()).unwrap();
+        let kv = KvStore::new(client.dir(".my/state"));
+
+        assert_eq!(kv.get_versioned::<u32>("n").unwrap(), None);
+        assert!(kv.put_if_unchanged("n", None, &1).unwrap());
+        assert!(!kv.put_if_unchanged("n", None, &2).unwrap());
+
+        let current = kv.get_versioned::<u32>("n").unwrap().unwrap();
+        assert_eq!(current.value, 1);
+        assert!(kv
+            .put_if_unchanged("n", Some(&current.version), &2)
+            .unwrap());
+        assert!(!kv
+            .put_if_unchanged("n", Some(&current.version), &3)
+            .unwrap());
+        assert_eq!(kv.get::<u32>("n").unwrap(), Some(2));
+        assert!(kv
+            .put_if_unchanged("n", Some(&Version::Unknown), &4)
+            .is_err());
+    }
+}
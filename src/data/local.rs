@@ -0,0 +1,359 @@
+//! Local filesystem storage backend for Algorithmia Data
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use algorithmia::Algorithmia;
+//! use algorithmia::data::LocalBackend;
+//!
+//! let mut client = Algorithmia::client("")?;
+//! client.backend(LocalBackend::new("/tmp/algorithmia-data"));
+//!
+//! // Written to `/tmp/algorithmia-data/.my/outputs/result.json`
+//! client.file(".my/outputs/result.json").put("{}")?;
+//! # Ok::<(), Box<std::error::Error>>(())
+//! ```
+
+use super::backend::{is_implicit_dir, paginate};
+use crate::data::{
+    DataAcl, DataConnector, DataUri, DirectoryPage, FileData, FileEntry, ListOptions,
+    ObjectMetadata, PutCondition, StorageBackend,
+};
+use crate::error::{Error, ResultExt};
+use crate::Body;
+use chrono::{DateTime, Utc};
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Serializes conditional writes, which are checked and written in separate steps
+static PUT_IF_LOCK: Mutex<()> = Mutex::new(());
+
+/// Storage backend that stores files and directories in a local directory
+///
+/// `data://` URIs map to paths relative to the root directory,
+/// so `data://.my/foo/bar.txt` is stored at `<root>/.my/foo/bar.txt`.
+/// Other connectors map to a hidden directory named after their scheme,
+/// so `s3://bucket/key.txt` is stored at `<root>/.s3/bucket/key.txt`.
+///
+/// The root of each connector and its immediate children (e.g. `data://.my` or an S3 bucket)
+/// always exist, and are created on demand. Files can otherwise only be written to existing
+/// directories, as with the Data API. ACLs are not supported: they are ignored when creating
+/// directories, and cannot be read or updated.
+///
+/// Streaming uploads are buffered in memory before they are written.
+/// Conditional writes (`put_if`) are only atomic with respect to other conditional writes
+/// in this process.
+#[derive(Debug, Clone)]
+pub struct LocalBackend {
+    root: PathBuf,
+}
+
+impl LocalBackend {
+    /// Store data in the directory `root`
+    pub fn new<P: Into<PathBuf>>(root: P) -> LocalBackend {
+        LocalBackend { root: root.into() }
+    }
+
+    /// The root directory of this backend
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Local path of a data URI
+    pub fn local_path(&self, uri: &DataUri) -> PathBuf {
+        let mut path = self.root.clone();
+        if *uri.connector() != DataConnector::Data {
+            path.push(format!(".{}", uri.connector().scheme()));
+        }
+        path.extend(uri.segments());
+        path
+    }
+
+    /// Check that the parent of `uri` exists, creating it if it is implicit
+    fn prepare_parent(&self, uri: &DataUri) -> Result<(), Error> {
+        let parent = match uri.parent() {
+            Some(parent) => parent,
+            None => bail!("'{}' is not a valid path for a file", uri),
+        };
+        let parent_path = self.local_path(&parent);
+        if is_implicit_dir(&parent) {
+            fs::create_dir_all(&parent_path)
+                .with_context(|| format!("error creating directory '{}'", parent))?;
+        } else if !parent_path.is_dir() {
+            bail!("directory '{}' does not exist", parent);
+        }
+        Ok(())
+    }
+}
+
+fn modified(metadata: &fs::Metadata) -> DateTime<Utc> {
+    metadata
+        .modified()
+        .map(DateTime::<Utc>::from)
+        .unwrap_or_else(|_| super::algo_epoch())
+}
+
+/// Number of files within a directory and its subdirectories
+fn count_files(path: &Path) -> io::Result<u64> {
+    let mut count = 0;
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            count += count_files(&entry.path())?;
+        } else {
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+impl StorageBackend for LocalBackend {
+    fn get(&self, uri: &DataUri, start: u64, end: Option<u64>) -> Result<FileData, Error> {
+        let path = self.local_path(uri);
+        if path.is_dir() {
+            bail!("'{}' is a directory, not a file", uri);
+        }
+        let mut file = match File::open(&path) {
+            Ok(file) => file,
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => {
+                bail!("file '{}' does not exist", uri)
+            }
+            Err(err) => return Err(err).with_context(|| format!("error reading file '{}'", uri)),
+        };
+        let metadata = file
+            .metadata()
+            .with_context(|| format!("error reading file '{}'", uri))?;
+
+        let len = metadata.len();
+        let end = end.map_or(len, |end| end.saturating_add(1).min(len));
+        let start = start.min(end);
+        file.seek(SeekFrom::Start(start))
+            .with_context(|| format!("error reading file '{}'", uri))?;
+        Ok(FileData::new(
+            file.take(end - start),
+            end - start,
+            modified(&metadata),
+        ))
+    }
+
+    fn put(&self, uri: &DataUri, mut body: Body) -> Result<(), Error> {
+        let path = self.local_path(uri);
+        if path.is_dir() || is_implicit_dir(uri) {
+            bail!("'{}' is a directory, not a file", uri);
+        }
+        self.prepare_parent(uri)?;
+        let content = match body.as_bytes() {
+            Some(bytes) => bytes,
+            None => body
+                .buffer()
+                .with_context(|| format!("error reading content for '{}'", uri))?,
+        };
+        fs::write(&path, content).with_context(|| format!("error writing file '{}'", uri))
+    }
+
+    fn put_if(&self, uri: &DataUri, condition: &PutCondition, body: Body) -> Result<bool, Error> {
+        let _guard = PUT_IF_LOCK
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if !condition.matches(self.head(uri)?.as_ref()) {
+            return Ok(false);
+        }
+        self.put(uri, body)?;
+        Ok(true)
+    }
+
+    fn delete_file(&self, uri: &DataUri) -> Result<(), Error> {
+        let path = self.local_path(uri);
+        if path.is_dir() {
+            bail!("'{}' is a directory, not a file", uri);
+        }
+        match fs::remove_file(&path) {
+            Ok(()) => Ok(()),
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => {
+                bail!("file '{}' does not exist", uri)
+            }
+            Err(err) => Err(err).with_context(|| format!("error deleting file '{}'", uri)),
+        }
+    }
+
+    fn rename(&self, from: &DataUri, to: &DataUri) -> Result<(), Error> {
+        let from_path = self.local_path(from);
+        let to_path = self.local_path(to);
+        if from_path.is_dir() {
+            bail!("'{}' is a directory, not a file", from);
+        }
+        if !from_path.exists() {
+            bail!("file '{}' does not exist", from);
+        }
+        if to_path.is_dir() || is_implicit_dir(to) {
+            bail!("'{}' is a directory, not a file", to);
+        }
+        self.prepare_parent(to)?;
+        fs::rename(&from_path, &to_path)
+            .with_context(|| format!("error moving '{}' to '{}'", from, to))
+    }
+
+    fn head(&self, uri: &DataUri) -> Result<Option<ObjectMetadata>, Error> {
+        match fs::metadata(self.local_path(uri)) {
+            Ok(ref metadata) if metadata.is_dir() => Ok(Some(ObjectMetadata::dir())),
+            Ok(metadata) => Ok(Some(ObjectMetadata::file(
+                metadata.len(),
+                modified(&metadata),
+            ))),
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => Ok(if is_implicit_dir(uri) {
+                Some(ObjectMetadata::dir())
+            } else {
+                None
+            }),
+            Err(err) => Err(err).with_context(|| format!("error inspecting '{}'", uri)),
+        }
+    }
+
+    fn list(&self, uri: &DataUri, options: &ListOptions) -> Result<DirectoryPage, Error> {
+        let path = self.local_path(uri);
+        let read_dir = match fs::read_dir(&path) {
+            Ok(read_dir) => read_dir,
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound && is_implicit_dir(uri) => {
+                return Ok(DirectoryPage::default())
+            }
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => {
+                bail!("directory '{}' does not exist", uri)
+            }
+            Err(err) => {
+                return Err(err).with_context(|| format!("error listing directory '{}'", uri))
+            }
+        };
+
+        let mut folders = Vec::new();
+        let mut files = Vec::new();
+        for entry in read_dir {
+            let entry = entry.with_context(|| format!("error listing directory '{}'", uri))?;
+            let name = match entry.file_name().into_string() {
+                Ok(name) => name,
+                // Data URIs cannot refer to files whose names are not valid UTF-8
+                Err(_) => continue,
+            };
+            let metadata = entry
+                .metadata()
+                .with_context(|| format!("error listing directory '{}'", uri))?;
+            if metadata.is_dir() {
+                folders.push(name);
+            } else {
+                files.push(FileEntry {
+                    name,
+                    size: metadata.len(),
+                    last_modified: modified(&metadata),
+                });
+            }
+        }
+        Ok(paginate(folders, files, options))
+    }
+
+    fn mkdir(&self, uri: &DataUri, _acl: Option<&DataAcl>) -> Result<(), Error> {
+        self.prepare_parent(uri)?;
+        match fs::create_dir(self.local_path(uri)) {
+            Ok(()) => Ok(()),
+            Err(ref err) if err.kind() == io::ErrorKind::AlreadyExists => {
+                bail!("'{}' already exists", uri)
+            }
+            Err(err) => Err(err).with_context(|| format!("error creating directory '{}'", uri)),
+        }
+    }
+
+    fn set_acl(&self, uri: &DataUri, _acl: &DataAcl) -> Result<(), Error> {
+        bail!(
+            "cannot update ACL of '{}': the local filesystem backend does not support ACLs",
+            uri
+        )
+    }
+
+    fn delete_dir(&self, uri: &DataUri, force: bool) -> Result<u64, Error> {
+        let path = self.local_path(uri);
+        if !path.is_dir() {
+            bail!("directory '{}' does not exist", uri);
+        }
+        let deleted =
+            count_files(&path).with_context(|| format!("error deleting directory '{}'", uri))?;
+        if force {
+            fs::remove_dir_all(&path)
+        } else {
+            let is_empty = fs::read_dir(&path)
+                .with_context(|| format!("error deleting directory '{}'", uri))?
+                .next()
+                .is_none();
+            if !is_empty {
+                bail!("directory '{}' is not empty", uri);
+            }
+            fs::remove_dir(&path)
+        }
+        .with_context(|| format!("error deleting directory '{}'", uri))?;
+        Ok(deleted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::test_support::LocalTempDir;
+    use crate::data::{DataItem, HasDataPath};
+    use crate::Algorithmia;
+
+    fn local_client(root: &Path) -> (Algorithmia, LocalBackend) {
+        let backend = LocalBackend::new(root);
+        let mut client = Algorithmia::client("").unwrap();
+        client.backend(backend.clone());
+        (client, backend)
+    }
+
+    #[test]
+    fn test_local_path() {
+        let backend = LocalBackend::new("/srv/data");
+        assert_eq!(
+            backend.local_path(&DataUri::from("data://.my/foo/bar.txt")),
+            Path::new("/srv/data/.my/foo/bar.txt")
+        );
+        assert_eq!(
+            backend.local_path(&DataUri::from("s3://bucket/key.txt")),
+            Path::new("/srv/data/.s3/bucket/key.txt")
+        );
+    }
+
+    #[test]
+    fn test_files_and_dirs() {
+        let root = LocalTempDir::new("local");
+        let (client, backend) = local_client(&root);
+        let dir = client.dir(".my/foo");
+        assert!(client.file(".my/foo/a.txt").put("a").is_err());
+        dir.create(DataAcl::default()).unwrap();
+
+        let file = client.file(".my/foo/a.txt");
+        file.put("hello world").unwrap();
+        assert_eq!(
+            fs::read_to_string(backend.root().join(".my/foo/a.txt")).unwrap(),
+            "hello world"
+        );
+        assert_eq!(file.metadata().unwrap().size, 11);
+        assert_eq!(file.get_range(6..).unwrap().into_string().unwrap(), "world");
+
+        client
+            .dir(".my/foo/sub")
+            .create(DataAcl::default())
+            .unwrap();
+        client.file(".my/foo/sub/b.txt").put("b").unwrap();
+        let names: Vec<_> = dir
+            .list()
+            .map(|item| match item.unwrap() {
+                DataItem::Dir(d) => format!("{}/", d.basename().unwrap()),
+                DataItem::File(f) => f.basename().unwrap(),
+            })
+            .collect();
+        assert_eq!(names, vec!["sub/", "a.txt"]);
+        assert!(dir.set_acl(DataAcl::default()).is_err());
+
+        assert!(dir.delete(false).is_err());
+        assert_eq!(dir.delete(true).unwrap().deleted, 2);
+        assert!(!dir.exists().unwrap());
+    }
+}
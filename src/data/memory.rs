@@ -0,0 +1,511 @@
+//! In-memory storage backend for Algorithmia Data
+//!
+//! # Examples
+//!
+//! ```
+//! use algorithmia::Algorithmia;
+//! use algorithmia::data::{DataAcl, MemoryBackend};
+//!
+//! let mut client = Algorithmia::client("")?;
+//! client.backend(MemoryBackend::new());
+//!
+//! let my_dir = client.dir(".my/my_dir");
+//! my_dir.create(DataAcl::default())?;
+//! my_dir.child::<algorithmia::data::DataFile>("hello.txt").put("hello")?;
+//! assert_eq!(my_dir.list().count(), 1);
+//! # Ok::<(), Box<std::error::Error>>(())
+//! ```
+
+use super::backend::{is_implicit_dir, paginate};
+use crate::data::{
+    DataAcl, DataUri, DirectoryPage, FileData, FileEntry, ListOptions, ObjectMetadata,
+    PutCondition, StorageBackend,
+};
+use crate::error::{Error, ResultExt};
+use crate::Body;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::sync::{Mutex, MutexGuard};
+
+/// Storage backend that holds files and directories in memory
+///
+/// Mirrors the behavior of the Data API: files can only be written to existing directories,
+/// and only empty directories can be deleted without `force`. The root of each connector
+/// and its immediate children (e.g. `data://.my` or an S3 bucket) always exist.
+/// Content is lost when the backend is dropped.
+#[derive(Debug, Default)]
+pub struct MemoryBackend {
+    entries: Mutex<HashMap<DataUri, Entry>>,
+}
+
+#[derive(Debug)]
+enum Entry {
+    File {
+        content: Vec<u8>,
+        last_modified: DateTime<Utc>,
+    },
+    Dir {
+        acl: DataAcl,
+    },
+}
+
+type Entries<'a> = MutexGuard<'a, HashMap<DataUri, Entry>>;
+
+impl MemoryBackend {
+    /// Create an empty in-memory backend
+    pub fn new() -> MemoryBackend {
+        MemoryBackend::default()
+    }
+
+    fn entries(&self) -> Entries<'_> {
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+fn is_dir(entries: &Entries<'_>, uri: &DataUri) -> bool {
+    match entries.get(uri) {
+        Some(Entry::Dir { .. }) => true,
+        Some(Entry::File { .. }) => false,
+        None => is_implicit_dir(uri),
+    }
+}
+
+fn check_parent(entries: &Entries<'_>, uri: &DataUri) -> Result<(), Error> {
+    match uri.parent() {
+        Some(ref parent) if is_dir(entries, parent) => Ok(()),
+        Some(parent) => bail!("directory '{}' does not exist", parent),
+        None => bail!("'{}' is not a valid path for a file", uri),
+    }
+}
+
+fn read_body(uri: &DataUri, mut body: Body) -> Result<Vec<u8>, Error> {
+    match body.as_bytes() {
+        Some(bytes) => Ok(bytes.to_vec()),
+        None => Ok(body
+            .buffer()
+            .with_context(|| format!("error reading content for '{}'", uri))?
+            .to_vec()),
+    }
+}
+
+fn write_file(entries: &mut Entries<'_>, uri: &DataUri, content: Vec<u8>) -> Result<(), Error> {
+    if is_dir(entries, uri) {
+        bail!("'{}' is a directory, not a file", uri);
+    }
+    check_parent(entries, uri)?;
+    entries.insert(
+        uri.clone(),
+        Entry::File {
+            content,
+            last_modified: Utc::now(),
+        },
+    );
+    Ok(())
+}
+
+impl StorageBackend for MemoryBackend {
+    fn get(&self, uri: &DataUri, start: u64, end: Option<u64>) -> Result<FileData, Error> {
+        let entries = self.entries();
+        match entries.get(uri) {
+            Some(Entry::File {
+                content,
+                last_modified,
+            }) => {
+                let len = content.len() as u64;
+                let end = end.map_or(len, |end| end.saturating_add(1).min(len));
+                let start = start.min(end);
+                let bytes = content[start as usize..end as usize].to_vec();
+                Ok(FileData::new(
+                    Cursor::new(bytes),
+                    end - start,
+                    *last_modified,
+                ))
+            }
+            Some(Entry::Dir { .. }) => bail!("'{}' is a directory, not a file", uri),
+            None => bail!("file '{}' does not exist", uri),
+        }
+    }
+
+    fn put(&self, uri: &DataUri, body: Body) -> Result<(), Error> {
+        let content = read_body(uri, body)?;
+        write_file(&mut self.entries(), uri, content)
+    }
+
+    fn put_if(&self, uri: &DataUri, condition: &PutCondition, body: Body) -> Result<bool, Error> {
+        let content = read_body(uri, body)?;
+        let mut entries = self.entries();
+        let current = match entries.get(uri) {
+            Some(Entry::File {
+                content,
+                last_modified,
+            }) => Some(ObjectMetadata::file(content.len() as u64, *last_modified)),
+            _ => None,
+        };
+        if !condition.matches(current.as_ref()) {
+            return Ok(false);
+        }
+        write_file(&mut entries, uri, content)?;
+        Ok(true)
+    }
+
+    fn delete_file(&self, uri: &DataUri) -> Result<(), Error> {
+        let mut entries = self.entries();
+        match entries.get(uri) {
+            Some(Entry::File { .. }) => {
+                entries.remove(uri);
+                Ok(())
+            }
+            Some(Entry::Dir { .. }) => bail!("'{}' is a directory, not a file", uri),
+            None => bail!("file '{}' does not exist", uri),
+        }
+    }
+
+    fn rename(&self, from: &DataUri, to: &DataUri) -> Result<(), Error> {
+        let mut entries = self.entries();
+        match entries.get(from) {
+            Some(Entry::File { .. }) => (),
+            Some(Entry::Dir { .. }) => bail!("'{}' is a directory, not a file", from),
+            None => bail!("file '{}' does not exist", from),
+        }
+        if is_dir(&entries, to) {
+            bail!("'{}' is a directory, not a file", to);
+        }
+        check_parent(&entries, to)?;
+        if let Some(entry) = entries.remove(from) {
+            entries.insert(to.clone(), entry);
+        }
+        Ok(())
+    }
+
+    fn head(&self, uri: &DataUri) -> Result<Option<ObjectMetadata>, Error> {
+        let entries = self.entries();
+        Ok(match entries.get(uri) {
+            Some(Entry::File {
+                content,
+                last_modified,
+            }) => Some(ObjectMetadata::file(content.len() as u64, *last_modified)),
+            Some(Entry::Dir { .. }) => Some(ObjectMetadata::dir()),
+            None if is_implicit_dir(uri) => Some(ObjectMetadata::dir()),
+            None => None,
+        })
+    }
+
+    fn list(&self, uri: &DataUri, options: &ListOptions) -> Result<DirectoryPage, Error> {
+        let entries = self.entries();
+        let acl = match entries.get(uri) {
+            Some(Entry::Dir { acl }) => acl.clone(),
+            Some(Entry::File { .. }) => bail!("'{}' is a file, not a directory", uri),
+            None if is_implicit_dir(uri) => DataAcl::default(),
+            None => bail!("directory '{}' does not exist", uri),
+        };
+
+        let mut folders = Vec::new();
+        let mut files = Vec::new();
+        for (child, entry) in entries.iter() {
+            if child.parent().as_ref() != Some(uri) {
+                continue;
+            }
+            // Children always have a file name
+            let name = child.file_name().unwrap_or_default().to_owned();
+            match entry {
+                Entry::Dir { .. } => folders.push(name),
+                Entry::File {
+                    content,
+                    last_modified,
+                } => files.push(FileEntry {
+                    name,
+                    size: content.len() as u64,
+                    last_modified: *last_modified,
+                }),
+            }
+        }
+        let mut page = paginate(folders, files, options);
+        if options.with_acl && uri.connector().supports_acl() {
+            page.acl = Some(acl);
+        }
+        Ok(page)
+    }
+
+    fn mkdir(&self, uri: &DataUri, acl: Option<&DataAcl>) -> Result<(), Error> {
+        let mut entries = self.entries();
+        if entries.contains_key(uri) || is_implicit_dir(uri) {
+            bail!("'{}' already exists", uri);
+        }
+        check_parent(&entries, uri)?;
+        entries.insert(
+            uri.clone(),
+            Entry::Dir {
+                acl: acl.cloned().unwrap_or_default(),
+            },
+        );
+        Ok(())
+    }
+
+    fn set_acl(&self, uri: &DataUri, acl: &DataAcl) -> Result<(), Error> {
+        let mut entries = self.entries();
+        if !is_dir(&entries, uri) {
+            bail!("directory '{}' does not exist", uri);
+        }
+        entries.insert(uri.clone(), Entry::Dir { acl: acl.clone() });
+        Ok(())
+    }
+
+    fn delete_dir(&self, uri: &DataUri, force: bool) -> Result<u64, Error> {
+        let mut entries = self.entries();
+        match entries.get(uri) {
+            Some(Entry::Dir { .. }) => (),
+            Some(Entry::File { .. }) => bail!("'{}' is a file, not a directory", uri),
+            None if is_implicit_dir(uri) => bail!("cannot delete root directory '{}'", uri),
+            None => bail!("directory '{}' does not exist", uri),
+        }
+
+        let descendants: Vec<DataUri> = entries
+            .keys()
+            .filter(|key| *key != uri && key.starts_with(uri))
+            .cloned()
+            .collect();
+        if !force && !descendants.is_empty() {
+            bail!("directory '{}' is not empty", uri);
+        }
+
+        let mut deleted = 0;
+        for key in descendants {
+            if let Some(Entry::File { .. }) = entries.remove(&key) {
+                deleted += 1;
+            }
+        }
+        entries.remove(uri);
+        Ok(deleted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{DataDir, DataFile, DataItem, HasDataPath, ReadAcl};
+    use crate::Algorithmia;
+
+    fn memory_client() -> Algorithmia {
+        let mut client = Algorithmia::client("").unwrap();
+        client.backend(MemoryBackend::new());
+        client
+    }
+
+    #[test]
+    fn test_file_round_trip() {
+        let client = memory_client();
+        client.dir(".my/foo").create(DataAcl::default()).unwrap();
+
+        let file = client.file(".my/foo/bar.txt");
+        assert!(!file.exists().unwrap());
+        file.put("hello world").unwrap();
+        assert!(file.exists().unwrap());
+        assert_eq!(file.metadata().unwrap().size, 11);
+        assert_eq!(file.get().unwrap().into_string().unwrap(), "hello world");
+        assert_eq!(file.get_range(6..).unwrap().into_string().unwrap(), "world");
+        assert_eq!(
+            file.get_range(0..=4).unwrap().into_string().unwrap(),
+            "hello"
+        );
+
+        file.delete().unwrap();
+        assert!(!file.exists().unwrap());
+        assert!(file.get().is_err());
+    }
+
+    #[test]
+    fn test_put_atomic() {
+        let client = memory_client();
+        client.dir(".my/foo").create(DataAcl::default()).unwrap();
+        let file = client.file(".my/foo/model.json");
+        file.put("old").unwrap();
+        file.put_atomic("new content").unwrap();
+        assert_eq!(file.get().unwrap().into_string().unwrap(), "new content");
+        // The temporary sibling was moved over the file
+        assert_eq!(client.dir(".my/foo").list().count(), 1);
+
+        assert!(client.file(".my/missing/x.json").put_atomic("x").is_err());
+    }
+
+    /// Memory backend without a native move, like the Data API
+    struct CopyOnlyBackend(MemoryBackend);
+
+    impl StorageBackend for CopyOnlyBackend {
+        fn get(&self, uri: &DataUri, start: u64, end: Option<u64>) -> Result<FileData, Error> {
+            self.0.get(uri, start, end)
+        }
+        fn put(&self, uri: &DataUri, body: Body) -> Result<(), Error> {
+            self.0.put(uri, body)
+        }
+        fn put_if(
+            &self,
+            uri: &DataUri,
+            condition: &PutCondition,
+            body: Body,
+        ) -> Result<bool, Error> {
+            self.0.put_if(uri, condition, body)
+        }
+        fn delete_file(&self, uri: &DataUri) -> Result<(), Error> {
+            self.0.delete_file(uri)
+        }
+        fn rename(&self, from: &DataUri, _to: &DataUri) -> Result<(), Error> {
+            bail!("cannot move '{}'", from)
+        }
+        fn supports_rename(&self) -> bool {
+            false
+        }
+        fn head(&self, uri: &DataUri) -> Result<Option<ObjectMetadata>, Error> {
+            self.0.head(uri)
+        }
+        fn list(&self, uri: &DataUri, options: &ListOptions) -> Result<DirectoryPage, Error> {
+            self.0.list(uri, options)
+        }
+        fn mkdir(&self, uri: &DataUri, acl: Option<&DataAcl>) -> Result<(), Error> {
+            self.0.mkdir(uri, acl)
+        }
+        fn set_acl(&self, uri: &DataUri, acl: &DataAcl) -> Result<(), Error> {
+            self.0.set_acl(uri, acl)
+        }
+        fn delete_dir(&self, uri: &DataUri, force: bool) -> Result<u64, Error> {
+            self.0.delete_dir(uri, force)
+        }
+    }
+
+    #[test]
+    fn test_put_atomic_by_copy() {
+        let mut client = Algorithmia::client("").unwrap();
+        client.backend(CopyOnlyBackend(MemoryBackend::new()));
+        client.dir(".my/foo").create(DataAcl::default()).unwrap();
+        let file = client.file(".my/foo/model.json");
+        file.put("old").unwrap();
+        file.put_atomic("new content").unwrap();
+        assert_eq!(file.get().unwrap().into_string().unwrap(), "new content");
+
+        file.put_atomic(Body::new(Cursor::new("streamed content")))
+            .unwrap();
+        assert_eq!(
+            file.get().unwrap().into_string().unwrap(),
+            "streamed content"
+        );
+        // The temporary siblings were deleted after being copied
+        assert_eq!(client.dir(".my/foo").list().count(), 1);
+    }
+
+    #[test]
+    fn test_missing_parent() {
+        let client = memory_client();
+        assert!(client.file(".my/missing/bar.txt").put("x").is_err());
+        assert!(client
+            .dir(".my/missing/sub")
+            .create(DataAcl::default())
+            .is_err());
+    }
+
+    #[test]
+    fn test_list_and_delete() {
+        let client = memory_client();
+        let dir = client.dir(".my/foo");
+        dir.create(DataAcl::default()).unwrap();
+        dir.child::<DataDir>("sub")
+            .create(ReadAcl::Private)
+            .unwrap();
+        dir.child::<DataFile>("b.txt").put("bb").unwrap();
+        dir.child::<DataFile>("a.txt").put("a").unwrap();
+        client.file(".my/foo/sub/c.txt").put("ccc").unwrap();
+
+        let names: Vec<_> = dir
+            .list()
+            .map(|item| match item.unwrap() {
+                DataItem::Dir(d) => format!("{}/", d.basename().unwrap()),
+                DataItem::File(f) => format!("{}:{}", f.basename().unwrap(), f.size),
+            })
+            .collect();
+        assert_eq!(names, vec!["sub/", "a.txt:1", "b.txt:2"]);
+
+        assert!(dir.delete(false).is_err());
+        assert_eq!(dir.delete(true).unwrap().deleted, 3);
+        assert!(!dir.exists().unwrap());
+        assert!(!client.file(".my/foo/sub/c.txt").exists().unwrap());
+    }
+
+    fn item_name(item: Result<DataItem, Error>) -> String {
+        match item.unwrap() {
+            DataItem::Dir(d) => format!("{}/", d.basename().unwrap()),
+            DataItem::File(f) => f.basename().unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_listing_pages() {
+        let client = memory_client();
+        let dir = client.dir(".my/foo");
+        dir.create(DataAcl::default()).unwrap();
+        dir.child::<DataDir>("sub")
+            .create(ReadAcl::Private)
+            .unwrap();
+        for name in &["a.txt", "b.txt", "c.txt", "d.txt"] {
+            dir.child::<DataFile>(name).put("x").unwrap();
+        }
+
+        let mut listing = dir.listing();
+        listing.page_size(2);
+        let first: Vec<_> = listing.by_ref().take(3).map(item_name).collect();
+        assert_eq!(first, vec!["sub/", "a.txt", "b.txt"]);
+        assert!(listing.marker().is_some());
+        assert!(listing.acl().is_some());
+
+        // Resume a new listing, mid-page, from another thread
+        let checkpoint = listing.checkpoint();
+        assert_eq!(checkpoint.offset, 1);
+        let mut resumed = dir.listing();
+        resumed.page_size(2).resume(checkpoint);
+        let rest = std::thread::spawn(move || resumed.map(item_name).collect::<Vec<_>>())
+            .join()
+            .unwrap();
+        assert_eq!(rest, vec!["c.txt", "d.txt"]);
+
+        let files: Vec<_> = dir.listing().files_only().map(item_name).collect();
+        assert_eq!(files, vec!["a.txt", "b.txt", "c.txt", "d.txt"]);
+        let folders: Vec<_> = dir.listing().folders_only().map(item_name).collect();
+        assert_eq!(folders, vec!["sub/"]);
+    }
+
+    #[test]
+    fn test_listing_missing_dir() {
+        let client = memory_client();
+        let mut listing = client.dir(".my/missing").listing();
+        assert!(listing.next().unwrap().is_err());
+        assert!(listing.next().is_none());
+    }
+
+    #[test]
+    fn test_acl() {
+        let client = memory_client();
+        let dir = client.dir(".my/foo");
+        dir.create(ReadAcl::Private).unwrap();
+        assert_eq!(ReadAcl::from(dir.acl().unwrap()), ReadAcl::Private);
+        dir.set_acl(ReadAcl::Public).unwrap();
+        assert_eq!(ReadAcl::from(dir.acl().unwrap()), ReadAcl::Public);
+    }
+
+    #[test]
+    fn test_glob() {
+        let client = memory_client();
+        client.dir(".my/runs").create(DataAcl::default()).unwrap();
+        client.file(".my/runs/a.csv").put("1").unwrap();
+        client.file(".my/runs/b.json").put("2").unwrap();
+
+        let matches: Vec<_> = client
+            .glob("data://.my/runs/*.csv")
+            .map(|item| match item.unwrap() {
+                DataItem::File(f) => f.to_data_uri(),
+                DataItem::Dir(d) => d.to_data_uri(),
+            })
+            .collect();
+        assert_eq!(matches, vec!["data://.my/runs/a.csv"]);
+        assert_eq!(client.glob("data://.my/runs/missing.txt").count(), 0);
+    }
+}
@@ -2,28 +2,81 @@
 //!
 //! Instantiate from the [`Algorithmia`](../struct.Algorithmia.html) struct
 
+pub use self::acl::*;
+#[cfg(feature = "archive")]
+pub use self::archive::*;
+pub use self::backend::*;
+pub use self::bulk::*;
+pub use self::cache::*;
+#[cfg(any(feature = "gzip", feature = "zstd"))]
+pub use self::compression::Compression;
+pub use self::connector::*;
+#[cfg(feature = "content-store")]
+pub use self::content::*;
 pub use self::dir::*;
+#[cfg(feature = "encryption")]
+pub use self::encryption::*;
 pub use self::file::*;
+pub use self::glob::*;
+pub use self::local::*;
+pub use self::memory::*;
 pub use self::object::*;
 pub use self::path::*;
+pub use self::progress::*;
+pub use self::records::*;
+pub use self::seekable::*;
+pub use self::temp::*;
+pub use self::uri::*;
+pub use self::usage::*;
+pub use self::watch::*;
+pub use self::writer::*;
 
 use crate::error::{err_msg, Error};
 use chrono::{DateTime, TimeZone, Utc};
-use headers::{ContentLength, Date, HeaderMapExt};
-use http::header::HeaderMap;
+use headers::{ContentLength, ContentType, Date, HeaderMapExt, LastModified};
+use http::header::{HeaderMap, ETAG};
+use mime::Mime;
 use std::ops::Deref;
 use std::time::SystemTime;
 
+mod acl;
+#[cfg(feature = "archive")]
+mod archive;
+mod backend;
+mod bulk;
+mod cache;
+#[cfg(any(feature = "gzip", feature = "zstd"))]
+mod compression;
+mod connector;
+#[cfg(feature = "content-store")]
+mod content;
 mod dir;
+#[cfg(feature = "encryption")]
+mod encryption;
 mod file;
+mod glob;
+pub mod kv;
+mod local;
+mod memory;
 mod object;
 mod path;
+mod progress;
+mod records;
+mod seekable;
+mod temp;
+#[cfg(test)]
+mod test_support;
+mod uri;
+mod usage;
+mod watch;
+mod writer;
 
 static DATA_BASE_PATH: &'static str = "v1/connector";
 
 use crate::client::header::{lossy_header, X_DATA_TYPE};
 
 /// Minimal representation of data type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DataType {
     File,
     Dir,
@@ -63,13 +116,7 @@ impl Deref for DataDirItem {
     }
 }
 
-struct HeaderData {
-    pub data_type: DataType,
-    pub content_length: Option<u64>,
-    pub last_modified: Option<DateTime<Utc>>,
-}
-
-fn parse_headers(headers: &HeaderMap) -> Result<HeaderData, Error> {
+fn parse_headers(headers: &HeaderMap) -> Result<ObjectMetadata, Error> {
     let data_type = match &headers.get(X_DATA_TYPE).map(lossy_header) {
         Some(dt) if dt == "directory" => DataType::Dir,
         Some(dt) if dt == "file" => DataType::File,
@@ -83,24 +130,28 @@ fn parse_headers(headers: &HeaderMap) -> Result<HeaderData, Error> {
     };
 
     let content_length = headers.typed_get::<ContentLength>().map(|c| c.0);
-    let last_modified = headers.typed_get::<Date>().map(|d| {
-        let time = SystemTime::from(d);
-        DateTime::<Utc>::from(time)
-    });
-
-    Ok(HeaderData {
+    let last_modified = headers
+        .typed_get::<LastModified>()
+        .map(SystemTime::from)
+        .map(DateTime::<Utc>::from);
+    let content_type = headers.typed_get::<ContentType>().map(Mime::from);
+    let etag = headers.get(ETAG).map(lossy_header);
+
+    Ok(ObjectMetadata {
         data_type: data_type,
-        content_length: content_length,
+        size: content_length,
         last_modified: last_modified,
+        content_type,
+        etag,
     })
 }
 
-fn parse_data_uri(data_uri: &str) -> String {
-    match data_uri {
-        p if p.contains("://") => p.split_terminator("://").collect::<Vec<_>>().join("/"),
-        p if p.starts_with('/') => format!("data/{}", &p[1..]),
-        p => format!("data/{}", p),
-    }
+/// Time of the response, the legacy fallback for `FileData::last_modified`
+fn response_date(headers: &HeaderMap) -> Option<DateTime<Utc>> {
+    headers
+        .typed_get::<Date>()
+        .map(SystemTime::from)
+        .map(DateTime::<Utc>::from)
 }
 
 fn algo_epoch() -> DateTime<Utc> {
@@ -110,31 +161,49 @@ fn algo_epoch() -> DateTime<Utc> {
 
 #[cfg(test)]
 mod tests {
-    use super::parse_data_uri;
+    use super::{parse_headers, DataType};
+    use http::header::{HeaderMap, HeaderValue};
 
     #[test]
-    fn test_parse_protocol() {
-        assert_eq!(parse_data_uri("data://"), "data");
-        assert_eq!(parse_data_uri("data://foo"), "data/foo");
-        assert_eq!(parse_data_uri("data://foo/"), "data/foo/");
-        assert_eq!(parse_data_uri("data://foo/bar"), "data/foo/bar");
-        assert_eq!(parse_data_uri("dropbox://"), "dropbox");
-        assert_eq!(parse_data_uri("dropbox://foo"), "dropbox/foo");
-        assert_eq!(parse_data_uri("dropbox://foo/"), "dropbox/foo/");
-        assert_eq!(parse_data_uri("dropbox://foo/bar"), "dropbox/foo/bar");
+    fn test_parse_file_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-data-type", HeaderValue::from_static("file"));
+        headers.insert("content-length", HeaderValue::from_static("1024"));
+        headers.insert("content-type", HeaderValue::from_static("text/csv"));
+        headers.insert("etag", HeaderValue::from_static("\"abc123\""));
+        headers.insert(
+            "last-modified",
+            HeaderValue::from_static("Sat, 14 Mar 2015 08:00:00 GMT"),
+        );
+        headers.insert(
+            "date",
+            HeaderValue::from_static("Sun, 18 Oct 2026 12:00:00 GMT"),
+        );
+
+        let metadata = parse_headers(&headers).unwrap();
+        assert!(matches!(metadata.data_type, DataType::File));
+        assert_eq!(metadata.size, Some(1024));
+        assert_eq!(metadata.content_type, Some(mime::TEXT_CSV));
+        assert_eq!(metadata.etag.as_deref(), Some("\"abc123\""));
+        assert_eq!(metadata.last_modified, Some(super::algo_epoch()));
     }
 
     #[test]
-    fn test_parse_leading_slash() {
-        assert_eq!(parse_data_uri("/foo"), "data/foo");
-        assert_eq!(parse_data_uri("/foo/"), "data/foo/");
-        assert_eq!(parse_data_uri("/foo/bar"), "data/foo/bar");
+    fn test_parse_headers_without_last_modified() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-data-type", HeaderValue::from_static("file"));
+        headers.insert(
+            "date",
+            HeaderValue::from_static("Sun, 18 Oct 2026 12:00:00 GMT"),
+        );
+
+        let metadata = parse_headers(&headers).unwrap();
+        assert_eq!(metadata.last_modified, None);
+        assert!(super::response_date(&headers).is_some());
     }
 
     #[test]
-    fn test_parse_unprefixed() {
-        assert_eq!(parse_data_uri("foo"), "data/foo");
-        assert_eq!(parse_data_uri("foo/"), "data/foo/");
-        assert_eq!(parse_data_uri("foo/bar"), "data/foo/bar");
+    fn test_parse_headers_missing_data_type() {
+        assert!(parse_headers(&HeaderMap::new()).is_err());
     }
 }
@@ -1,7 +1,7 @@
-use super::{algo_epoch, parse_data_uri, parse_headers};
+use super::algo_epoch;
 use crate::client::HttpClient;
 use crate::data::*;
-use crate::error::{process_http_response, Error, ResultExt};
+use crate::error::Error;
 
 /// Algorithmia data object (file or directory)
 pub struct DataObject {
@@ -14,7 +14,7 @@ impl HasDataPath for DataObject {
     fn new(client: HttpClient, path: &str) -> Self {
         DataObject {
             client: client,
-            path: parse_data_uri(path).to_string(),
+            path: DataUri::from(path).to_path(),
         }
     }
     #[doc(hidden)]
@@ -42,16 +42,7 @@ impl DataObject {
     /// # Ok::<(), Box<std::error::Error>>(())
     /// ```
     pub fn get_type(&self) -> Result<DataType, Error> {
-        let url = self.to_url()?;
-        let req = self.client.head(url);
-        let res = req
-            .send()
-            .with_context(|| format!("request error getting type of '{}'", self.to_data_uri()))
-            .and_then(process_http_response)
-            .with_context(|| format!("response error getting type of '{}'", self.to_data_uri()))?;
-
-        let metadata = parse_headers(res.headers())?;
-        Ok(metadata.data_type)
+        Ok(self.metadata()?.data_type)
     }
 
     /// Determine if a data URI is for a file or directory and convert into the appropriate type
@@ -68,26 +59,31 @@ impl DataObject {
     /// # Ok::<(), Box<std::error::Error>>(())
     /// ```
     pub fn into_type(self) -> Result<DataItem, Error> {
-        let metadata = {
-            let url = self.to_url()?;
-            let req = self.client.head(url);
-            let res = req
-                .send()
-                .with_context(|| format!("request error getting type of '{}'", self.to_data_uri()))
-                .and_then(process_http_response)
-                .with_context(|| {
-                    format!("response error getting type of '{}'", self.to_data_uri())
-                })?;
-            parse_headers(res.headers())?
-        };
+        let metadata = self.metadata()?;
+        Ok(self.into_item(metadata))
+    }
+
+    /// Like `into_type`, but `None` if nothing exists at this data URI
+    pub(crate) fn try_into_type(self) -> Result<Option<DataItem>, Error> {
+        let metadata = self.backend().head(&self.data_uri())?;
+        Ok(metadata.map(|metadata| self.into_item(metadata)))
+    }
+
+    fn metadata(&self) -> Result<ObjectMetadata, Error> {
+        match self.backend().head(&self.data_uri())? {
+            Some(metadata) => Ok(metadata),
+            None => bail!("'{}' does not exist", self.to_data_uri()),
+        }
+    }
 
+    fn into_item(self, metadata: ObjectMetadata) -> DataItem {
         match metadata.data_type {
-            DataType::Dir => Ok(DataItem::Dir(DataDirItem { dir: self.into() })),
-            DataType::File => Ok(DataItem::File(DataFileItem {
-                size: metadata.content_length.unwrap_or(0),
+            DataType::Dir => DataItem::Dir(DataDirItem { dir: self.into() }),
+            DataType::File => DataItem::File(DataFileItem {
+                size: metadata.size.unwrap_or(0),
                 last_modified: metadata.last_modified.unwrap_or_else(algo_epoch),
                 file: self.into(),
-            })),
+            }),
         }
     }
 }
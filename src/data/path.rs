@@ -1,9 +1,9 @@
-use crate::client::header::X_ERROR_MESSAGE;
 use crate::data::*;
-use crate::error::{ApiError, Error, ResultExt};
+use crate::error::{Error, ResultExt};
 
 use crate::client::HttpClient;
-use reqwest::{StatusCode, Url};
+use reqwest::Url;
+use std::sync::Arc;
 
 /// Trait used for types that can be represented with an Algorithmia Data URI
 pub trait HasDataPath {
@@ -13,6 +13,12 @@ pub trait HasDataPath {
     fn path(&self) -> &str;
     #[doc(hidden)]
     fn client(&self) -> &HttpClient;
+    #[doc(hidden)]
+    fn set_progress_handler(&mut self, _handler: Option<Arc<dyn ProgressHandler>>) {}
+    #[doc(hidden)]
+    fn backend(&self) -> Arc<dyn StorageBackend> {
+        self.client().backend()
+    }
 
     /// Get the API Endpoint URL for a particular data URI
     fn to_url(&self) -> Result<Url, Error> {
@@ -35,11 +41,51 @@ pub trait HasDataPath {
     /// assert_eq!(my_dir.to_data_uri(), "data://.my/my_dir");
     /// ```
     fn to_data_uri(&self) -> String {
-        let parts = self.path().splitn(2, '/').collect::<Vec<_>>();
-        match parts.len() {
-            1 => format!("{}://", parts[0]),
-            _ => parts.join("://"),
+        self.data_uri().to_string()
+    }
+
+    /// Get the typed `DataUri` of a given Data Object
+    ///
+    /// ```
+    /// # use algorithmia::Algorithmia;
+    /// # use algorithmia::data::HasDataPath;
+    /// # let client = Algorithmia::client("111112222233333444445555566").unwrap();
+    /// let my_file = client.file("data://.my/my_dir/my_file.csv");
+    /// assert_eq!(my_file.data_uri().extension(), Some("csv"));
+    /// ```
+    fn data_uri(&self) -> DataUri {
+        DataUri::from_path(self.path())
+    }
+
+    /// Get the connector that a given Data Object is stored on
+    ///
+    /// ```
+    /// # use algorithmia::Algorithmia;
+    /// # use algorithmia::data::{DataConnector, HasDataPath};
+    /// # let client = Algorithmia::client("111112222233333444445555566").unwrap();
+    /// assert_eq!(client.dir(".my/my_dir").connector(), DataConnector::Data);
+    /// assert_eq!(client.dir("s3://bucket/dir").connector(), DataConnector::S3);
+    /// ```
+    fn connector(&self) -> DataConnector {
+        self.data_uri().connector().clone()
+    }
+
+    /// Get the bucket (or container) of a Data Object on a bucket-based connector
+    ///
+    /// ```
+    /// # use algorithmia::Algorithmia;
+    /// # use algorithmia::data::HasDataPath;
+    /// # let client = Algorithmia::client("111112222233333444445555566").unwrap();
+    /// let my_file = client.file("s3://my-bucket/path/to/file.csv");
+    /// assert_eq!(my_file.bucket().unwrap(), "my-bucket");
+    /// assert!(client.file(".my/my_dir/file.csv").bucket().is_none());
+    /// ```
+    fn bucket(&self) -> Option<String> {
+        let uri = self.data_uri();
+        if !uri.connector().has_buckets() {
+            return None;
         }
+        uri.segments().first().cloned()
     }
 
     /// Get the parent off a given Data Object
@@ -52,16 +98,9 @@ pub trait HasDataPath {
     /// assert_eq!(my_file.parent().unwrap().to_data_uri(), "data://.my/my_dir");
     /// ```
     fn parent(&self) -> Option<DataDir> {
-        // Remove trailing slash and split
-        let parts: Vec<&str> = self.path().split_terminator('/').collect();
-        // Reformat using protocol while dropping last part
-        let parent_uri = match parts.len() {
-            0 | 1 => None,
-            2 => Some(format!("{}://", parts[0])),
-            len => Some(format!("{}://{}", parts[0], parts[1..(len - 1)].join("/"))),
-        };
-        // Initialize new DataDir from the parent_uri
-        parent_uri.map(|uri| DataDir::new(self.client().clone(), &uri))
+        self.data_uri()
+            .parent()
+            .map(|uri| DataDir::new(self.client().clone(), &uri.to_string()))
     }
 
     /// Get the basename from the Data Object's path (i.e. unix `basename`)
@@ -75,7 +114,7 @@ pub trait HasDataPath {
     /// # Ok::<(), Box<std::error::Error>>(())
     /// ```
     fn basename(&self) -> Option<String> {
-        self.path().rsplitn(2, '/').next().map(String::from)
+        self.data_uri().file_name().map(String::from)
     }
 
     /// Determine if a file or directory exists for a particular data URI
@@ -89,27 +128,10 @@ pub trait HasDataPath {
     /// # Ok::<(), Box<std::error::Error>>(())
     /// ```
     fn exists(&self) -> Result<bool, Error> {
-        let url = self.to_url()?;
-        let client = self.client();
-        let req = client.head(url);
-
-        let res = req
-            .send()
+        let metadata = self
+            .backend()
+            .head(&self.data_uri())
             .with_context(|| format!("checking existence of '{}'", self.to_data_uri()))?;
-        match res.status() {
-            StatusCode::OK => Ok(true),
-            StatusCode::NOT_FOUND => Ok(false),
-            status => {
-                let msg = match res
-                    .headers()
-                    .get(X_ERROR_MESSAGE)
-                    .and_then(|x| x.to_str().ok())
-                {
-                    Some(err_header) => format!("{}: {}", status, err_header),
-                    None => format!("{}", status),
-                };
-                Err(ApiError::from(msg).into())
-            }
-        }
+        Ok(metadata.is_some())
     }
 }
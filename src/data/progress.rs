@@ -0,0 +1,223 @@
+//! Progress reporting for uploads and downloads of Algorithmia Data Files
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use algorithmia::Algorithmia;
+//! use algorithmia::data::Progress;
+//!
+//! let client = Algorithmia::client("111112222233333444445555566")?;
+//! client
+//!     .dir(".my/my_dir")
+//!     .progress(|p: &Progress| match p.total {
+//!         Some(total) => println!("{}: {}/{} bytes", p.data_uri, p.bytes, total),
+//!         None => println!("{}: {} bytes", p.data_uri, p.bytes),
+//!     })
+//!     .put_file("/path/to/model.bin")?;
+//! # Ok::<(), Box<std::error::Error>>(())
+//! ```
+
+use std::io::{self, Read};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Minimum time between progress reports for a single transfer
+const REPORT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Direction of a data transfer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Uploading to the Data API
+    Upload,
+    /// Downloading from the Data API
+    Download,
+}
+
+/// Snapshot of a transfer's progress
+#[derive(Debug, Clone)]
+pub struct Progress {
+    /// Data URI of the file being transferred
+    pub data_uri: String,
+    /// Whether the file is being uploaded or downloaded
+    pub direction: Direction,
+    /// Bytes transferred so far (including any bytes resumed from a previous download)
+    pub bytes: u64,
+    /// Total bytes of the transfer, if known
+    pub total: Option<u64>,
+    /// Time elapsed since the transfer started
+    pub elapsed: Duration,
+    resumed_from: u64,
+}
+
+impl Progress {
+    /// Average transfer rate in bytes per second
+    pub fn rate(&self) -> f64 {
+        let secs = self.elapsed.as_secs() as f64
+            + f64::from(self.elapsed.subsec_nanos()) / 1_000_000_000.0;
+        if secs > 0.0 {
+            (self.bytes - self.resumed_from) as f64 / secs
+        } else {
+            0.0
+        }
+    }
+
+    /// Fraction of the transfer completed (between 0 and 1), if the total is known
+    pub fn fraction(&self) -> Option<f64> {
+        self.total.map(|total| match total {
+            0 => 1.0,
+            total => self.bytes as f64 / total as f64,
+        })
+    }
+}
+
+/// Receives progress reports for data transfers
+///
+/// Implemented for any `Fn(&Progress)` closure that is `Send + Sync`.
+pub trait ProgressHandler: Send + Sync {
+    /// Called periodically while a transfer makes progress, and once when it completes
+    fn on_progress(&self, progress: &Progress);
+}
+
+impl<F> ProgressHandler for F
+where
+    F: Fn(&Progress) + Send + Sync,
+{
+    fn on_progress(&self, progress: &Progress) {
+        self(progress)
+    }
+}
+
+/// Tracks a single transfer and throttles reports to its `ProgressHandler`
+pub(crate) struct ProgressTracker {
+    handler: Arc<dyn ProgressHandler>,
+    progress: Progress,
+    started: Instant,
+    last_report: Option<Instant>,
+    finished: bool,
+}
+
+impl ProgressTracker {
+    pub(crate) fn new(
+        handler: Arc<dyn ProgressHandler>,
+        data_uri: String,
+        direction: Direction,
+        total: Option<u64>,
+        resumed_from: u64,
+    ) -> ProgressTracker {
+        ProgressTracker {
+            handler,
+            progress: Progress {
+                data_uri,
+                direction,
+                bytes: resumed_from,
+                total,
+                elapsed: Duration::from_secs(0),
+                resumed_from,
+            },
+            started: Instant::now(),
+            last_report: None,
+            finished: false,
+        }
+    }
+
+    pub(crate) fn advance(&mut self, bytes: u64) {
+        self.progress.bytes += bytes;
+        if Some(self.progress.bytes) == self.progress.total {
+            self.finish();
+        } else if self
+            .last_report
+            .is_none_or(|last| last.elapsed() >= REPORT_INTERVAL)
+        {
+            self.report();
+        }
+    }
+
+    pub(crate) fn finish(&mut self) {
+        if !self.finished {
+            self.finished = true;
+            self.report();
+        }
+    }
+
+    fn report(&mut self) {
+        let now = Instant::now();
+        self.progress.elapsed = now - self.started;
+        self.last_report = Some(now);
+        self.handler.on_progress(&self.progress);
+    }
+}
+
+/// Reader adapter that reports the bytes read through a `ProgressTracker`
+pub(crate) struct ProgressReader<R> {
+    inner: R,
+    tracker: ProgressTracker,
+}
+
+impl<R: Read> ProgressReader<R> {
+    pub(crate) fn new(inner: R, tracker: ProgressTracker) -> ProgressReader<R> {
+        ProgressReader { inner, tracker }
+    }
+}
+
+impl<R: Read> Read for ProgressReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        match n {
+            0 if !buf.is_empty() => self.tracker.finish(),
+            n => self.tracker.advance(n as u64),
+        }
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    fn recorder() -> (Arc<Mutex<Vec<Progress>>>, Arc<dyn ProgressHandler>) {
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink = reports.clone();
+        let handler = move |p: &Progress| sink.lock().unwrap().push(p.clone());
+        (reports, Arc::new(handler))
+    }
+
+    #[test]
+    fn test_reader_reports_completion() {
+        let (reports, handler) = recorder();
+        let tracker = ProgressTracker::new(
+            handler,
+            "data://.my/foo/bar.txt".into(),
+            Direction::Download,
+            Some(11),
+            0,
+        );
+        let mut reader = ProgressReader::new(&b"hello world"[..], tracker);
+        let mut text = String::new();
+        reader.read_to_string(&mut text).unwrap();
+
+        let reports = reports.lock().unwrap();
+        let last = reports.last().unwrap();
+        assert_eq!(last.bytes, 11);
+        assert_eq!(last.fraction(), Some(1.0));
+        assert_eq!(reports.iter().filter(|p| p.bytes == 11).count(), 1);
+    }
+
+    #[test]
+    fn test_resumed_progress() {
+        let (reports, handler) = recorder();
+        let mut tracker = ProgressTracker::new(
+            handler,
+            "data://.my/foo/bar.txt".into(),
+            Direction::Download,
+            Some(100),
+            60,
+        );
+        tracker.advance(40);
+
+        let reports = reports.lock().unwrap();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].bytes, 100);
+        assert_eq!(reports[0].resumed_from, 60);
+    }
+}
@@ -0,0 +1,150 @@
+//! Iterators that decode data files one line or record at a time
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use algorithmia::Algorithmia;
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Event {
+//!     id: u64,
+//!     kind: String,
+//! }
+//!
+//! let client = Algorithmia::client("111112222233333444445555566")?;
+//! for event in client.file(".my/my_dir/events.ndjson").get()?.json_records::<Event>() {
+//!     let event = event?;
+//!     println!("{}: {}", event.id, event.kind);
+//! }
+//! # Ok::<(), Box<std::error::Error>>(())
+//! ```
+
+use crate::data::FileData;
+use crate::error::{Error, ResultExt};
+use serde::de::DeserializeOwned;
+use serde_json::de::IoRead;
+use serde_json::StreamDeserializer;
+use std::io::{BufRead, BufReader, Lines};
+
+impl FileData {
+    /// Iterate over the lines of the file as they are downloaded
+    ///
+    /// Like `BufRead::lines`, line endings are stripped and invalid UTF-8 is an error.
+    pub fn lines(self) -> Lines<BufReader<FileData>> {
+        BufReader::new(self).lines()
+    }
+
+    /// Decode a stream of JSON values (e.g. newline-delimited JSON) one record at a time
+    pub fn json_records<T: DeserializeOwned>(self) -> JsonRecords<T> {
+        let de = serde_json::Deserializer::from_reader(BufReader::new(self));
+        JsonRecords {
+            inner: de.into_iter(),
+            index: 0,
+        }
+    }
+
+    /// Decode CSV records into a type one record at a time
+    ///
+    /// The first row is treated as a header whose names map to the fields of `T`.
+    #[cfg(feature = "csv")]
+    pub fn csv_records<T: DeserializeOwned>(self) -> CsvRecords<T> {
+        CsvRecords {
+            inner: csv::Reader::from_reader(self).into_deserialize(),
+        }
+    }
+}
+
+/// Iterator over JSON values decoded from a `FileData`
+///
+/// Created with [`FileData::json_records`](struct.FileData.html#method.json_records)
+pub struct JsonRecords<T> {
+    inner: StreamDeserializer<'static, IoRead<BufReader<FileData>>, T>,
+    index: usize,
+}
+
+impl<T: DeserializeOwned> Iterator for JsonRecords<T> {
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let record = self.inner.next()?;
+        self.index += 1;
+        let index = self.index;
+        Some(record.with_context(|| format!("JSON decoding error in record {}", index)))
+    }
+}
+
+/// Iterator over CSV records decoded from a `FileData`
+///
+/// Created with [`FileData::csv_records`](struct.FileData.html#method.csv_records)
+#[cfg(feature = "csv")]
+pub struct CsvRecords<T> {
+    inner: csv::DeserializeRecordsIntoIter<FileData, T>,
+}
+
+#[cfg(feature = "csv")]
+impl<T: DeserializeOwned> Iterator for CsvRecords<T> {
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let record = self.inner.next()?;
+        Some(record.context("CSV decoding error"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Record {
+        id: u32,
+        name: String,
+    }
+
+    #[test]
+    fn test_lines() {
+        let data = FileData::from_bytes(b"one\ntwo\r\nthree".to_vec());
+        let lines: Vec<String> = data.lines().map(Result::unwrap).collect();
+        assert_eq!(lines, vec!["one", "two", "three"]);
+    }
+
+    #[test]
+    fn test_json_records() {
+        let ndjson = b"{\"id\":1,\"name\":\"a\"}\n{\"id\":2,\"name\":\"b\"}\n";
+        let data = FileData::from_bytes(ndjson.to_vec());
+        let records: Vec<Record> = data.json_records().map(Result::unwrap).collect();
+        assert_eq!(
+            records,
+            vec![
+                Record {
+                    id: 1,
+                    name: "a".into()
+                },
+                Record {
+                    id: 2,
+                    name: "b".into()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_json_records_error() {
+        let data = FileData::from_bytes(b"{\"id\":1,\"name\":\"a\"}\n{\"id\":".to_vec());
+        let mut records = data.json_records::<Record>();
+        assert!(records.next().unwrap().is_ok());
+        let err = records.next().unwrap().unwrap_err();
+        assert!(err.to_string().contains("record 2"));
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn test_csv_records() {
+        let data = FileData::from_bytes(b"id,name\n1,a\n2,b\n".to_vec());
+        let records: Vec<Record> = data.csv_records().map(Result::unwrap).collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].name, "b");
+    }
+}
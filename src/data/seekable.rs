@@ -0,0 +1,201 @@
+//! Random access reader over Algorithmia Data Files
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use algorithmia::Algorithmia;
+//! use std::io::{Read, Seek, SeekFrom};
+//!
+//! let client = Algorithmia::client("111112222233333444445555566")?;
+//! let mut reader = client.file(".my/my_dir/data.parquet").open_seekable()?;
+//! reader.read_ahead(64 * 1024);
+//!
+//! let mut magic = [0; 4];
+//! reader.seek(SeekFrom::End(-4))?;
+//! reader.read_exact(&mut magic)?;
+//! # Ok::<(), Box<std::error::Error>>(())
+//! ```
+
+use crate::data::{DataFile, HasDataPath};
+use std::cmp;
+use std::io::{self, Read, Seek, SeekFrom};
+
+/// Default number of bytes requested each time the `SeekableFile` buffer is refilled
+const DEFAULT_READ_AHEAD: usize = 1024 * 1024;
+
+/// Reader that supports `Seek` over a `DataFile` using range requests
+///
+/// Created with [`DataFile::open_seekable`](struct.DataFile.html#method.open_seekable)
+pub struct SeekableFile {
+    file: DataFile,
+    size: Option<u64>,
+    pos: u64,
+    buf: Vec<u8>,
+    buf_start: u64,
+    read_ahead: usize,
+}
+
+impl SeekableFile {
+    pub(crate) fn new(file: DataFile, size: Option<u64>) -> SeekableFile {
+        SeekableFile {
+            file,
+            size,
+            pos: 0,
+            buf: Vec::new(),
+            buf_start: 0,
+            read_ahead: DEFAULT_READ_AHEAD,
+        }
+    }
+
+    /// Size of the file in bytes, if reported by the API or found by reading to its end
+    pub fn size(&self) -> Option<u64> {
+        self.size
+    }
+
+    /// Builder method to configure the minimum number of bytes fetched per range request
+    pub fn read_ahead(&mut self, bytes: usize) -> &mut SeekableFile {
+        self.read_ahead = cmp::max(bytes, 1);
+        self
+    }
+
+    fn is_buffered(&self) -> bool {
+        self.pos >= self.buf_start && self.pos < self.buf_start + self.buf.len() as u64
+    }
+
+    fn fill_buf(&mut self, min_len: usize) -> io::Result<()> {
+        let mut end = self
+            .pos
+            .saturating_add(cmp::max(self.read_ahead, min_len) as u64 - 1);
+        if let Some(size) = self.size {
+            end = cmp::min(end, size - 1);
+        }
+
+        self.buf.clear();
+        self.buf_start = self.pos;
+        let mut data = self
+            .file
+            .request_file(self.pos, Some(end))
+            .map_err(io::Error::other)?;
+        data.read_to_end(&mut self.buf)?;
+
+        // A short read that returned any bytes ends exactly at the end of the file
+        let len = self.buf.len() as u64;
+        if len > 0 && len < end - self.pos + 1 {
+            self.size = Some(self.pos + len);
+        }
+        Ok(())
+    }
+}
+
+impl Read for SeekableFile {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        if out.is_empty() || self.size.is_some_and(|size| self.pos >= size) {
+            return Ok(0);
+        }
+        if !self.is_buffered() {
+            self.fill_buf(out.len())?;
+        }
+
+        let offset = (self.pos - self.buf_start) as usize;
+        let available = self.buf.len().saturating_sub(offset);
+        let n = cmp::min(out.len(), available);
+        out[..n].copy_from_slice(&self.buf[offset..offset + n]);
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl Seek for SeekableFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let (base, offset) = match pos {
+            SeekFrom::Start(n) => {
+                self.pos = n;
+                return Ok(n);
+            }
+            SeekFrom::Current(offset) => (self.pos, offset),
+            SeekFrom::End(offset) => match self.size {
+                Some(size) => (size, offset),
+                None => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!(
+                            "cannot seek from end of '{}': size unknown",
+                            self.file.to_data_uri()
+                        ),
+                    ))
+                }
+            },
+        };
+
+        let new_pos = if offset >= 0 {
+            base.checked_add(offset as u64)
+        } else {
+            base.checked_sub(offset.wrapping_neg() as u64)
+        };
+        match new_pos {
+            Some(n) => {
+                self.pos = n;
+                Ok(n)
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::MemoryBackend;
+    use crate::Algorithmia;
+
+    fn mock_reader(size: Option<u64>) -> SeekableFile {
+        let client = Algorithmia::client("").unwrap();
+        SeekableFile::new(client.file("data://.my/foo/bar.bin"), size)
+    }
+
+    #[test]
+    fn test_seek() {
+        let mut reader = mock_reader(Some(100));
+        assert_eq!(reader.seek(SeekFrom::Start(10)).unwrap(), 10);
+        assert_eq!(reader.seek(SeekFrom::Current(5)).unwrap(), 15);
+        assert_eq!(reader.seek(SeekFrom::Current(-15)).unwrap(), 0);
+        assert_eq!(reader.seek(SeekFrom::End(-4)).unwrap(), 96);
+        assert!(reader.seek(SeekFrom::Current(-97)).is_err());
+    }
+
+    #[test]
+    fn test_seek_end_unknown_size() {
+        let mut reader = mock_reader(None);
+        assert!(reader.seek(SeekFrom::End(0)).is_err());
+    }
+
+    #[test]
+    fn test_read_at_end() {
+        let mut reader = mock_reader(Some(100));
+        reader.seek(SeekFrom::End(0)).unwrap();
+        assert_eq!(reader.read(&mut [0; 8]).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_read_unknown_size() {
+        let mut client = Algorithmia::client("").unwrap();
+        client.backend(MemoryBackend::new());
+        let file = client.file("data://.my/bar.txt");
+        file.put("0123456789").unwrap();
+
+        let mut reader = SeekableFile::new(file, None);
+        reader.read_ahead(4);
+        let mut content = String::new();
+        reader.read_to_string(&mut content).unwrap();
+        assert_eq!(content, "0123456789");
+        assert_eq!(reader.size(), Some(10));
+
+        let mut reader = SeekableFile::new(client.file("data://.my/bar.txt"), None);
+        reader.seek(SeekFrom::Start(u64::MAX - 1)).unwrap();
+        assert_eq!(reader.read(&mut [0; 8]).unwrap(), 0);
+        assert_eq!(reader.size(), None);
+    }
+}
@@ -0,0 +1,155 @@
+//! Temporary data directories that are deleted when dropped
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use algorithmia::Algorithmia;
+//! use algorithmia::data::{DataFile, HasDataPath};
+//!
+//! let client = Algorithmia::client("111112222233333444445555566")?;
+//! let scratch = client.temp_dir()?;
+//! scratch.child::<DataFile>("input.txt").put("hello")?;
+//! println!("working in {}", scratch.to_data_uri());
+//!
+//! // Deleted here, or when `scratch` is dropped (including on panic)
+//! scratch.close()?;
+//! # Ok::<(), Box<std::error::Error>>(())
+//! ```
+
+use crate::data::{DataAcl, DataDir, DirectoryDeleted};
+use crate::error::Error;
+use chrono::Utc;
+use std::ops::Deref;
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Distinguishes temporary directories created concurrently by this process
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Uniquely named `DataDir` that is deleted, with its contents, when dropped
+///
+/// Created with [`Algorithmia::temp_dir`](../struct.Algorithmia.html#method.temp_dir)
+/// or [`DataDir::create_temp`](struct.DataDir.html#method.create_temp).
+/// Errors deleting the directory on drop are ignored; use `close` to handle them.
+pub struct TempDataDir {
+    dir: Option<DataDir>,
+}
+
+impl TempDataDir {
+    pub(crate) fn new_in(parent: &DataDir, prefix: &str) -> Result<TempDataDir, Error> {
+        let name = format!(
+            "{}{:x}-{:x}-{:x}",
+            prefix,
+            Utc::now().timestamp_millis(),
+            process::id(),
+            TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        );
+        let dir: DataDir = parent.try_child(&name)?;
+        dir.create(DataAcl::default())?;
+        Ok(TempDataDir { dir: Some(dir) })
+    }
+
+    /// Delete the directory and its contents now, returning any error
+    pub fn close(mut self) -> Result<DirectoryDeleted, Error> {
+        match self.dir.take() {
+            Some(dir) => dir.delete(true),
+            None => unreachable!("temporary directory is only taken when consumed"),
+        }
+    }
+
+    /// Keep the directory instead of deleting it, e.g. to inspect it after a failed test
+    pub fn persist(mut self) -> DataDir {
+        match self.dir.take() {
+            Some(dir) => dir,
+            None => unreachable!("temporary directory is only taken when consumed"),
+        }
+    }
+}
+
+impl Deref for TempDataDir {
+    type Target = DataDir;
+    fn deref(&self) -> &DataDir {
+        match self.dir {
+            Some(ref dir) => dir,
+            None => unreachable!("temporary directory is only taken when consumed"),
+        }
+    }
+}
+
+impl Drop for TempDataDir {
+    fn drop(&mut self) {
+        if let Some(dir) = self.dir.take() {
+            let _ = dir.delete(true);
+        }
+    }
+}
+
+/// Create a new local temporary file named with `prefix`, for spooling content
+///
+/// The file is created exclusively, so an existing file or symbolic link
+/// at a predictable path is never opened in its place.
+#[cfg(any(feature = "archive", feature = "content-store"))]
+pub(crate) fn create_spool_file(
+    prefix: &str,
+) -> std::io::Result<(std::path::PathBuf, std::fs::File)> {
+    loop {
+        let path = std::env::temp_dir().join(format!(
+            "algorithmia-{}-{:x}-{:x}-{:x}",
+            prefix,
+            process::id(),
+            Utc::now().timestamp_millis(),
+            TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        match std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path)
+        {
+            Ok(file) => return Ok((path, file)),
+            Err(ref err) if err.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::{DataFile, HasDataPath, MemoryBackend};
+    use crate::Algorithmia;
+
+    fn memory_client() -> Algorithmia {
+        let mut client = Algorithmia::client("").unwrap();
+        client.backend(MemoryBackend::new());
+        client
+    }
+
+    #[test]
+    fn test_temp_dir_cleanup() {
+        let client = memory_client();
+        let (a, b) = (client.temp_dir().unwrap(), client.temp_dir().unwrap());
+        assert_ne!(a.to_data_uri(), b.to_data_uri());
+        assert!(a.to_data_uri().starts_with("data://.my/tmp-"));
+
+        let file_uri = a.child::<DataFile>("x.txt").to_data_uri();
+        client.file(&*file_uri).put("x").unwrap();
+        let uri = a.to_data_uri();
+        drop(a);
+        assert!(!client.dir(&*uri).exists().unwrap());
+
+        let sub = b.create_temp("run-").unwrap();
+        let sub_uri = sub.to_data_uri();
+        assert!(sub_uri.starts_with(&format!("{}/run-", b.to_data_uri())));
+        sub.close().unwrap();
+        assert!(!client.dir(&*sub_uri).exists().unwrap());
+        assert_eq!(b.close().unwrap().deleted, 0);
+    }
+
+    #[test]
+    fn test_temp_dir_persist() {
+        let client = memory_client();
+        let dir = client.temp_dir().unwrap().persist();
+        assert!(dir.exists().unwrap());
+        dir.delete(false).unwrap();
+    }
+}
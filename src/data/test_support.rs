@@ -0,0 +1,42 @@
+//! Fixtures shared by the tests of data modules
+
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::{env, fs};
+
+/// Distinguishes local temporary directories of tests running concurrently
+static LOCAL_TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Uniquely named local directory for tests, deleted with its contents when dropped
+pub(crate) struct LocalTempDir {
+    path: PathBuf,
+}
+
+impl LocalTempDir {
+    pub(crate) fn new(prefix: &str) -> LocalTempDir {
+        let path = env::temp_dir().join(format!(
+            "algorithmia-{}-{:x}-{:x}",
+            prefix,
+            process::id(),
+            LOCAL_TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        LocalTempDir { path }
+    }
+}
+
+impl Deref for LocalTempDir {
+    type Target = Path;
+    fn deref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for LocalTempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
@@ -0,0 +1,395 @@
+//! Validated and normalized Algorithmia data URIs
+//!
+//! # Examples
+//!
+//! ```
+//! use algorithmia::data::DataUri;
+//!
+//! let uri: DataUri = "data://.my/runs//2026-01/../2026-02/".parse()?;
+//! assert_eq!(uri.to_string(), "data://.my/runs/2026-02");
+//! assert_eq!(uri.owner(), Some(".my"));
+//!
+//! let file = uri.join("results.csv");
+//! assert_eq!(file.file_name(), Some("results.csv"));
+//! assert_eq!(file.extension(), Some("csv"));
+//! assert_eq!(file.parent(), Some(uri));
+//! # Ok::<(), Box<std::error::Error>>(())
+//! ```
+
+use crate::data::DataConnector;
+use crate::error::Error;
+use serde::de::{self, Deserialize, Deserializer};
+use serde::{Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// A normalized data URI: a connector scheme followed by path segments
+///
+/// Parsing with `FromStr` validates the URI. Converting with `From<&str>` is lenient:
+/// an invalid scheme is kept as an unknown connector and `..` segments at the root are ignored.
+/// In both cases, paths without a scheme are assumed to be `data://` paths,
+/// empty and `.` segments are removed, `..` removes the preceding segment,
+/// and trailing slashes are dropped.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DataUri {
+    connector: DataConnector,
+    segments: Vec<String>,
+}
+
+impl DataUri {
+    fn parse(uri: &str, strict: bool) -> Result<DataUri, Error> {
+        let (scheme, path) = match uri.find("://") {
+            Some(i) => (&uri[..i], &uri[i + 3..]),
+            None => ("data", uri),
+        };
+        let connector = if strict {
+            scheme.parse()?
+        } else {
+            DataConnector::from(scheme)
+        };
+
+        let mut uri = DataUri {
+            connector,
+            segments: Vec::new(),
+        };
+        uri.push_segments(path, strict)?;
+        Ok(uri)
+    }
+
+    /// Convert from the API path form of a URI, e.g. `data/.my/foo`
+    pub(crate) fn from_path(path: &str) -> DataUri {
+        let (scheme, path) = match path.find('/') {
+            Some(i) => (&path[..i], &path[i + 1..]),
+            None => (path, ""),
+        };
+        let mut uri = DataUri {
+            connector: DataConnector::from(scheme),
+            segments: Vec::new(),
+        };
+        let _ = uri.push_segments(path, false);
+        uri
+    }
+
+    /// The API path form of this URI, e.g. `data/.my/foo`
+    pub(crate) fn to_path(&self) -> String {
+        let mut path = self.connector.scheme().to_string();
+        for segment in &self.segments {
+            path.push('/');
+            path.push_str(segment);
+        }
+        path
+    }
+
+    fn push_segments(&mut self, path: &str, strict: bool) -> Result<(), Error> {
+        for segment in path.split('/') {
+            match segment {
+                "" | "." => (),
+                ".." => {
+                    if self.segments.pop().is_none() && strict {
+                        bail!(
+                            "data URI path '{}' escapes the {} root",
+                            path,
+                            self.connector
+                        );
+                    }
+                }
+                s => self.segments.push(s.to_string()),
+            }
+        }
+        Ok(())
+    }
+
+    /// The connector this URI refers to
+    pub fn connector(&self) -> &DataConnector {
+        &self.connector
+    }
+
+    /// The owner of a `data://` URI, e.g. `.my`, `.algo` or a username
+    pub fn owner(&self) -> Option<&str> {
+        match self.connector {
+            DataConnector::Data => self.segments.first().map(String::as_str),
+            _ => None,
+        }
+    }
+
+    /// The normalized path segments following the scheme
+    pub fn segments(&self) -> &[String] {
+        &self.segments
+    }
+
+    /// Whether this URI is the root of its connector, e.g. `data://`
+    pub fn is_root(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Resolve a relative path against this URI
+    ///
+    /// `..` segments that would go above the connector root are ignored.
+    pub fn join(&self, path: &str) -> DataUri {
+        let mut uri = self.clone();
+        let _ = uri.push_segments(path, false);
+        uri
+    }
+
+    /// Append a relative path of names below this URI
+    ///
+    /// Unlike `join`, this fails if `path` has `.` or `..` segments,
+    /// so the result is always this URI or one of its descendants.
+    ///
+    /// ```
+    /// # use algorithmia::data::DataUri;
+    /// let dir: DataUri = "data://.my/foo".parse()?;
+    /// assert_eq!(dir.join_child("bar/baz.txt")?.to_string(), "data://.my/foo/bar/baz.txt");
+    /// assert!(dir.join_child("../bar").is_err());
+    /// # Ok::<(), Box<std::error::Error>>(())
+    /// ```
+    pub fn join_child(&self, path: &str) -> Result<DataUri, Error> {
+        let mut uri = self.clone();
+        for segment in path.split('/') {
+            match segment {
+                "" => (),
+                "." | ".." => bail!("'{}' is not a path below '{}'", path, self),
+                s => uri.segments.push(s.to_string()),
+            }
+        }
+        Ok(uri)
+    }
+
+    /// Resolve a relative path below this URI, where `..` never removes segments of this URI
+    pub(crate) fn join_below(&self, path: &str) -> DataUri {
+        let mut uri = self.clone();
+        for segment in path.split('/') {
+            match segment {
+                "" | "." => (),
+                ".." => {
+                    if uri.segments.len() > self.segments.len() {
+                        uri.segments.pop();
+                    }
+                }
+                s => uri.segments.push(s.to_string()),
+            }
+        }
+        uri
+    }
+
+    /// The ancestor of this URI with its first `len` segments
+    pub(crate) fn truncated(&self, len: usize) -> DataUri {
+        DataUri {
+            connector: self.connector.clone(),
+            segments: self.segments[..len.min(self.segments.len())].to_vec(),
+        }
+    }
+
+    /// The URI of the parent directory, or `None` at the connector root
+    pub fn parent(&self) -> Option<DataUri> {
+        if self.is_root() {
+            return None;
+        }
+        let mut parent = self.clone();
+        parent.segments.pop();
+        Some(parent)
+    }
+
+    /// The final segment of the path, or `None` at the connector root
+    pub fn file_name(&self) -> Option<&str> {
+        self.segments.last().map(String::as_str)
+    }
+
+    /// The extension of the file name, without the leading dot
+    ///
+    /// Like `std::path::Path`, a leading dot (e.g. `.hidden`) does not start an extension.
+    pub fn extension(&self) -> Option<&str> {
+        let name = self.file_name()?;
+        match name.rfind('.') {
+            Some(0) | None => None,
+            Some(i) => Some(&name[i + 1..]),
+        }
+    }
+
+    /// Whether `base` is this URI or one of its ancestors
+    pub fn starts_with(&self, base: &DataUri) -> bool {
+        self.connector == base.connector && self.segments.starts_with(&base.segments)
+    }
+
+    /// The path of this URI relative to `base`, if `base` is this URI or one of its ancestors
+    pub fn strip_prefix(&self, base: &DataUri) -> Option<String> {
+        if !self.starts_with(base) {
+            return None;
+        }
+        Some(self.segments[base.segments.len()..].join("/"))
+    }
+}
+
+impl fmt::Display for DataUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}://{}",
+            self.connector.scheme(),
+            self.segments.join("/")
+        )
+    }
+}
+
+impl FromStr for DataUri {
+    type Err = Error;
+    fn from_str(uri: &str) -> Result<Self, Self::Err> {
+        DataUri::parse(uri, true)
+    }
+}
+
+impl<'a> From<&'a str> for DataUri {
+    fn from(uri: &'a str) -> Self {
+        DataUri::parse(uri, false).expect("lenient data URI parsing is infallible")
+    }
+}
+
+impl<'a> From<&'a String> for DataUri {
+    fn from(uri: &'a String) -> Self {
+        DataUri::from(uri.as_str())
+    }
+}
+
+impl From<String> for DataUri {
+    fn from(uri: String) -> Self {
+        DataUri::from(uri.as_str())
+    }
+}
+
+impl<'a> From<&'a DataUri> for DataUri {
+    fn from(uri: &'a DataUri) -> Self {
+        uri.clone()
+    }
+}
+
+impl Serialize for DataUri {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for DataUri {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let uri = String::deserialize(deserializer)?;
+        uri.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn uri(s: &str) -> DataUri {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(uri("data://.my/foo/").to_string(), "data://.my/foo");
+        assert_eq!(uri("data:///.my//foo").to_string(), "data://.my/foo");
+        assert_eq!(uri("data://.my/./foo/../bar").to_string(), "data://.my/bar");
+        assert_eq!(uri("/.my/foo").to_string(), "data://.my/foo");
+        assert_eq!(uri(".my/foo").to_string(), "data://.my/foo");
+        assert_eq!(uri("data://").to_string(), "data://");
+        assert_eq!(uri("s3://bucket/key").to_string(), "s3://bucket/key");
+    }
+
+    #[test]
+    fn test_strict_parsing() {
+        assert!("data://.my/../..".parse::<DataUri>().is_err());
+        assert!("da ta://foo".parse::<DataUri>().is_err());
+        assert_eq!(DataUri::from("data://.my/../.."), uri("data://"));
+    }
+
+    #[test]
+    fn test_owner() {
+        assert_eq!(uri("data://.my/foo").owner(), Some(".my"));
+        assert_eq!(uri("data://anowell/foo").owner(), Some("anowell"));
+        assert_eq!(uri("data://").owner(), None);
+        assert_eq!(uri("s3://bucket/foo").owner(), None);
+    }
+
+    #[test]
+    fn test_join_and_parent() {
+        let dir = uri("data://.my/foo");
+        assert_eq!(dir.join("bar/baz.txt"), uri("data://.my/foo/bar/baz.txt"));
+        assert_eq!(dir.join("../bar"), uri("data://.my/bar"));
+        assert_eq!(dir.join("../../../.."), uri("data://"));
+        assert_eq!(dir.parent(), Some(uri("data://.my")));
+        assert_eq!(uri("data://.my").parent(), Some(uri("data://")));
+        assert_eq!(uri("data://").parent(), None);
+    }
+
+    #[test]
+    fn test_join_child() {
+        let dir = uri("data://.my/foo");
+        assert_eq!(
+            dir.join_child("bar//baz.txt").unwrap(),
+            uri("data://.my/foo/bar/baz.txt")
+        );
+        assert_eq!(dir.join_child("").unwrap(), dir);
+        assert!(dir.join_child("../bar").is_err());
+        assert!(dir.join_child("bar/../../baz").is_err());
+        assert!(dir.join_child("./bar").is_err());
+        assert_eq!(dir.truncated(1), uri("data://.my"));
+    }
+
+    #[test]
+    fn test_file_name_and_extension() {
+        let file = uri("data://.my/foo/archive.tar.gz");
+        assert_eq!(file.file_name(), Some("archive.tar.gz"));
+        assert_eq!(file.extension(), Some("gz"));
+        assert_eq!(uri("data://.my/.hidden").extension(), None);
+        assert_eq!(uri("data://.my/README").extension(), None);
+        assert_eq!(uri("data://").file_name(), None);
+    }
+
+    #[test]
+    fn test_strip_prefix() {
+        let file = uri("data://.my/foo/bar/baz.txt");
+        assert_eq!(
+            file.strip_prefix(&uri("data://.my/foo")).as_deref(),
+            Some("bar/baz.txt")
+        );
+        assert_eq!(file.strip_prefix(&file).as_deref(), Some(""));
+        assert_eq!(file.strip_prefix(&uri("data://.my/fo")), None);
+        assert_eq!(file.strip_prefix(&uri("s3://.my/foo")), None);
+    }
+
+    #[test]
+    fn test_parse_schemes() {
+        for (input, path) in &[
+            ("data://", "data"),
+            ("data://foo/", "data/foo"),
+            ("data://foo/bar", "data/foo/bar"),
+            ("dropbox://", "dropbox"),
+            ("dropbox://foo/bar/", "dropbox/foo/bar"),
+            ("/foo/bar/", "data/foo/bar"),
+            ("foo/bar", "data/foo/bar"),
+        ] {
+            assert_eq!(uri(input).to_path(), *path);
+        }
+    }
+
+    #[test]
+    fn test_api_path() {
+        assert_eq!(uri("data://.my/foo").to_path(), "data/.my/foo");
+        assert_eq!(uri("dropbox://").to_path(), "dropbox");
+        assert_eq!(DataUri::from_path("data/.my/foo/"), uri("data://.my/foo"));
+        assert_eq!(DataUri::from_path("dropbox"), uri("dropbox://"));
+    }
+
+    #[test]
+    fn test_serde() {
+        let file = uri("data://.my/foo.txt");
+        let json = serde_json::to_string(&file).unwrap();
+        assert_eq!(json, r#""data://.my/foo.txt""#);
+        assert_eq!(serde_json::from_str::<DataUri>(&json).unwrap(), file);
+        let err = serde_json::from_str::<DataUri>(r#""data://..""#).unwrap_err();
+        assert!(
+            err.to_string().contains("escapes the data:// root"),
+            "{}",
+            err
+        );
+    }
+}
@@ -0,0 +1,281 @@
+//! Disk usage summaries of data directories
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use algorithmia::Algorithmia;
+//! use chrono::Duration;
+//!
+//! let client = Algorithmia::client("111112222233333444445555566")?;
+//! let usage = client
+//!     .dir(".my/datasets")
+//!     .usage()
+//!     .depth(1)
+//!     .by_extension()
+//!     .by_age(&[Duration::days(30), Duration::days(365)])
+//!     .run()?;
+//!
+//! println!("{}: {} files, {} bytes", usage.data_uri, usage.files, usage.bytes);
+//! for subdir in &usage.subdirs {
+//!     println!("  {}: {} bytes", subdir.data_uri, subdir.bytes);
+//! }
+//! for (ext, totals) in &usage.by_extension {
+//!     println!("  *.{}: {} bytes", ext, totals.bytes);
+//! }
+//! # Ok::<(), Box<std::error::Error>>(())
+//! ```
+
+use crate::data::{DataDir, DataItem, HasDataPath};
+use crate::error::Error;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::BTreeMap;
+
+/// Builder for summarizing the disk usage of a `DataDir`
+///
+/// Created with [`DataDir::usage`](struct.DataDir.html#method.usage).
+#[derive(Clone)]
+pub struct UsageBuilder {
+    dir: DataDir,
+    depth: Option<usize>,
+    by_extension: bool,
+    age_thresholds: Option<Vec<Duration>>,
+}
+
+/// Number of files and their total size
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UsageTotals {
+    /// Number of files
+    pub files: u64,
+    /// Total size of the files in bytes
+    pub bytes: u64,
+}
+
+/// Usage of files whose age is within a range
+#[derive(Debug, Clone, PartialEq)]
+pub struct AgeBucket {
+    /// Minimum age (inclusive) of files in this bucket
+    pub min_age: Duration,
+    /// Maximum age (exclusive) of files in this bucket, or `None` for the oldest bucket
+    pub max_age: Option<Duration>,
+    /// Files in this bucket
+    pub totals: UsageTotals,
+}
+
+/// Disk usage of a directory, including all of its subdirectories
+#[derive(Debug, Clone)]
+pub struct DiskUsage {
+    /// Data URI of the directory
+    pub data_uri: String,
+    /// Number of files in the directory and its subdirectories
+    pub files: u64,
+    /// Total size in bytes of the files in the directory and its subdirectories
+    pub bytes: u64,
+    /// Usage of each subdirectory (sorted by name), up to the requested depth
+    pub subdirs: Vec<DiskUsage>,
+    /// Usage by lowercase file extension (without the dot), if requested
+    ///
+    /// Files without an extension are counted under `""`.
+    pub by_extension: BTreeMap<String, UsageTotals>,
+    /// Usage by age since last modified, youngest first, if requested
+    pub by_age: Vec<AgeBucket>,
+}
+
+impl UsageBuilder {
+    pub(crate) fn new(dir: DataDir) -> UsageBuilder {
+        UsageBuilder {
+            dir,
+            depth: None,
+            by_extension: false,
+            age_thresholds: None,
+        }
+    }
+
+    /// Builder method to only break down usage of subdirectories up to `depth` levels deep
+    ///
+    /// Files in deeper subdirectories are still counted in the totals. By default,
+    /// every subdirectory is included, and a depth of `0` only reports totals.
+    pub fn depth(&mut self, depth: usize) -> &mut UsageBuilder {
+        self.depth = Some(depth);
+        self
+    }
+
+    /// Builder method to break down usage by file extension
+    pub fn by_extension(&mut self) -> &mut UsageBuilder {
+        self.by_extension = true;
+        self
+    }
+
+    /// Builder method to break down usage by age, split at each threshold
+    ///
+    /// For example, thresholds of 30 and 365 days produce buckets for files modified
+    /// within 30 days, between 30 and 365 days ago, and more than 365 days ago.
+    pub fn by_age(&mut self, thresholds: &[Duration]) -> &mut UsageBuilder {
+        let mut thresholds = thresholds.to_vec();
+        thresholds.sort();
+        thresholds.dedup();
+        self.age_thresholds = Some(thresholds);
+        self
+    }
+
+    /// Recursively list the directory and summarize its usage
+    pub fn run(&self) -> Result<DiskUsage, Error> {
+        self.walk(&self.dir, 0, Utc::now())
+    }
+
+    fn walk(&self, dir: &DataDir, level: usize, now: DateTime<Utc>) -> Result<DiskUsage, Error> {
+        let mut usage = DiskUsage {
+            data_uri: dir.to_data_uri(),
+            files: 0,
+            bytes: 0,
+            subdirs: Vec::new(),
+            by_extension: BTreeMap::new(),
+            by_age: self.age_buckets(),
+        };
+
+        for item in dir.listing() {
+            match item? {
+                DataItem::Dir(d) => {
+                    let subdir = self.walk(&d, level + 1, now)?;
+                    usage.add(&subdir);
+                    if self.depth.is_none_or(|depth| level < depth) {
+                        usage.subdirs.push(subdir);
+                    }
+                }
+                DataItem::File(f) => {
+                    usage.files += 1;
+                    usage.bytes += f.size;
+                    if self.by_extension {
+                        let name = f.basename().unwrap_or_default();
+                        let totals = usage.by_extension.entry(extension(&name)).or_default();
+                        totals.files += 1;
+                        totals.bytes += f.size;
+                    }
+                    let age = now.signed_duration_since(f.last_modified);
+                    let bucket = usage
+                        .by_age
+                        .iter_mut()
+                        .find(|bucket| bucket.max_age.is_none_or(|max| age < max));
+                    if let Some(bucket) = bucket {
+                        bucket.totals.files += 1;
+                        bucket.totals.bytes += f.size;
+                    }
+                }
+            }
+        }
+
+        usage.subdirs.sort_by(|a, b| a.data_uri.cmp(&b.data_uri));
+        Ok(usage)
+    }
+
+    fn age_buckets(&self) -> Vec<AgeBucket> {
+        let thresholds = match self.age_thresholds {
+            Some(ref thresholds) => thresholds,
+            None => return Vec::new(),
+        };
+        let mut min_age = Duration::zero();
+        let mut buckets = Vec::new();
+        for threshold in thresholds {
+            buckets.push(AgeBucket {
+                min_age,
+                max_age: Some(*threshold),
+                totals: UsageTotals::default(),
+            });
+            min_age = *threshold;
+        }
+        buckets.push(AgeBucket {
+            min_age,
+            max_age: None,
+            totals: UsageTotals::default(),
+        });
+        buckets
+    }
+}
+
+impl DiskUsage {
+    /// Add the usage of a subdirectory to this directory
+    fn add(&mut self, subdir: &DiskUsage) {
+        self.files += subdir.files;
+        self.bytes += subdir.bytes;
+        for (ext, totals) in &subdir.by_extension {
+            let entry = self.by_extension.entry(ext.clone()).or_default();
+            entry.files += totals.files;
+            entry.bytes += totals.bytes;
+        }
+        for (bucket, sub_bucket) in self.by_age.iter_mut().zip(&subdir.by_age) {
+            bucket.totals.files += sub_bucket.totals.files;
+            bucket.totals.bytes += sub_bucket.totals.bytes;
+        }
+    }
+}
+
+fn extension(name: &str) -> String {
+    match name.rfind('.') {
+        // Leading dots mark hidden files rather than extensions
+        Some(idx) if idx > 0 => name[idx + 1..].to_lowercase(),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{DataAcl, MemoryBackend};
+    use crate::Algorithmia;
+
+    #[test]
+    fn test_extension() {
+        assert_eq!(extension("data.CSV"), "csv");
+        assert_eq!(extension("archive.tar.gz"), "gz");
+        assert_eq!(extension("README"), "");
+        assert_eq!(extension(".hidden"), "");
+    }
+
+    #[test]
+    fn test_usage() {
+        let mut client = Algorithmia::client("").unwrap();
+        client.backend(MemoryBackend::new());
+        for dir in &[".my/root", ".my/root/a", ".my/root/a/deep", ".my/root/b"] {
+            client.dir(*dir).create(DataAcl::default()).unwrap();
+        }
+        client.file(".my/root/top.csv").put("1234").unwrap();
+        client.file(".my/root/a/x.csv").put("12").unwrap();
+        client.file(".my/root/a/deep/y.json").put("123").unwrap();
+        client.file(".my/root/b/z").put("1").unwrap();
+
+        let usage = client
+            .dir(".my/root")
+            .usage()
+            .depth(1)
+            .by_extension()
+            .by_age(&[Duration::days(1)])
+            .run()
+            .unwrap();
+        assert_eq!((usage.files, usage.bytes), (4, 10));
+        let subdirs: Vec<_> = usage
+            .subdirs
+            .iter()
+            .map(|d| (d.data_uri.as_str(), d.files, d.bytes, d.subdirs.len()))
+            .collect();
+        assert_eq!(
+            subdirs,
+            vec![
+                ("data://.my/root/a", 2, 5, 0),
+                ("data://.my/root/b", 1, 1, 0)
+            ]
+        );
+        assert_eq!(
+            usage.by_extension["csv"],
+            UsageTotals { files: 2, bytes: 6 }
+        );
+        assert_eq!(usage.by_extension[""], UsageTotals { files: 1, bytes: 1 });
+        assert_eq!(usage.by_age.len(), 2);
+        assert_eq!(
+            usage.by_age[0].totals,
+            UsageTotals {
+                files: 4,
+                bytes: 10
+            }
+        );
+        assert_eq!(usage.by_age[1].totals, UsageTotals::default());
+    }
+}
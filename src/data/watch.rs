@@ -0,0 +1,291 @@
+//! Change detection by polling data directories
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use algorithmia::Algorithmia;
+//! use algorithmia::data::{ChangeKind, Snapshot};
+//! use std::fs;
+//! use std::time::Duration;
+//!
+//! let client = Algorithmia::client("111112222233333444445555566")?;
+//! let mut watcher = client.dir(".my/inbox").watch();
+//! watcher.interval(Duration::from_secs(30));
+//!
+//! // Resume from the last saved snapshot to avoid re-emitting existing files
+//! if let Ok(json) = fs::read_to_string("inbox-snapshot.json") {
+//!     watcher.resume_from(serde_json::from_str::<Snapshot>(&json)?);
+//! }
+//!
+//! while let Some(event) = watcher.next() {
+//!     let event = event?;
+//!     if event.kind == ChangeKind::Created {
+//!         println!("new file: {}", event.data_uri);
+//!     }
+//!     fs::write("inbox-snapshot.json", serde_json::to_string(watcher.snapshot())?)?;
+//! }
+//! # Ok::<(), Box<std::error::Error>>(())
+//! ```
+
+use crate::data::{DataDir, DataItem, HasDataPath};
+use crate::error::Error;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::thread;
+use std::time::Duration;
+
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Files of a watched directory, keyed by data URI
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct Snapshot {
+    /// Size and last modified timestamp of each file
+    pub files: BTreeMap<String, SnapshotEntry>,
+}
+
+/// State of a file within a `Snapshot`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SnapshotEntry {
+    /// Size of the file in bytes
+    pub size: u64,
+    /// Last modified timestamp
+    pub last_modified: DateTime<Utc>,
+}
+
+/// Kind of change to a watched file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    /// File was added
+    Created,
+    /// File size or last modified timestamp changed
+    Modified,
+    /// File was removed
+    Deleted,
+}
+
+/// Change to a file detected by a `Watcher`
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchEvent {
+    /// Kind of change
+    pub kind: ChangeKind,
+    /// Data URI of the file
+    pub data_uri: String,
+    /// State of the file after the change, or before it was deleted
+    pub entry: SnapshotEntry,
+}
+
+/// Watches a `DataDir` for changes by periodically listing it
+///
+/// Created with [`DataDir::watch`](struct.DataDir.html#method.watch). Iterating a watcher
+/// lists the directory immediately, then waits for the polling interval before each
+/// subsequent listing; it never ends on its own. Errors listing the directory are returned
+/// from the iterator, and polling continues with the next interval.
+///
+/// Without a previous snapshot, every file present at the first listing is reported as created.
+/// `snapshot` reflects every event returned so far, so it can be persisted and later passed to
+/// `resume_from` to only report changes since.
+pub struct Watcher {
+    dir: DataDir,
+    recursive: bool,
+    interval: Duration,
+    snapshot: Snapshot,
+    pending: VecDeque<WatchEvent>,
+    polled: bool,
+}
+
+impl Watcher {
+    pub(crate) fn new(dir: DataDir) -> Watcher {
+        Watcher {
+            dir,
+            recursive: false,
+            interval: DEFAULT_INTERVAL,
+            snapshot: Snapshot::default(),
+            pending: VecDeque::new(),
+            polled: false,
+        }
+    }
+
+    /// Builder method to also watch files in subdirectories
+    pub fn recursive(&mut self, recursive: bool) -> &mut Watcher {
+        self.recursive = recursive;
+        self
+    }
+
+    /// Builder method to set the time between listings when iterating (default: 60 seconds)
+    pub fn interval(&mut self, interval: Duration) -> &mut Watcher {
+        self.interval = interval;
+        self
+    }
+
+    /// Builder method to only report changes since a previous snapshot
+    pub fn resume_from(&mut self, snapshot: Snapshot) -> &mut Watcher {
+        self.snapshot = snapshot;
+        self.pending.clear();
+        self
+    }
+
+    /// Snapshot of the directory, as of the last event returned
+    pub fn snapshot(&self) -> &Snapshot {
+        &self.snapshot
+    }
+
+    /// List the directory once, returning all changes since the snapshot
+    ///
+    /// Events not yet returned by the iterator are included.
+    pub fn poll(&mut self) -> Result<Vec<WatchEvent>, Error> {
+        self.refresh()?;
+        let events: Vec<WatchEvent> = self.pending.drain(..).collect();
+        for event in &events {
+            self.snapshot.apply(event);
+        }
+        Ok(events)
+    }
+
+    fn refresh(&mut self) -> Result<(), Error> {
+        self.polled = true;
+        let mut current = Snapshot::default();
+        self.list_files(&self.dir, &mut current)?;
+        self.pending = self.snapshot.diff(&current).into();
+        Ok(())
+    }
+
+    fn list_files(&self, dir: &DataDir, snapshot: &mut Snapshot) -> Result<(), Error> {
+        let mut listing = dir.listing();
+        if !self.recursive {
+            listing.files_only();
+        }
+        for item in listing {
+            match item? {
+                DataItem::Dir(d) => self.list_files(&d, snapshot)?,
+                DataItem::File(f) => {
+                    let entry = SnapshotEntry {
+                        size: f.size,
+                        last_modified: f.last_modified,
+                    };
+                    snapshot.files.insert(f.to_data_uri(), entry);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Iterator for Watcher {
+    type Item = Result<WatchEvent, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                self.snapshot.apply(&event);
+                return Some(Ok(event));
+            }
+            if self.polled {
+                thread::sleep(self.interval);
+            }
+            if let Err(err) = self.refresh() {
+                return Some(Err(err));
+            }
+        }
+    }
+}
+
+impl Snapshot {
+    /// Changes from this snapshot to `other`, sorted by data URI
+    pub fn diff(&self, other: &Snapshot) -> Vec<WatchEvent> {
+        let mut events = Vec::new();
+        for (uri, entry) in &other.files {
+            let kind = match self.files.get(uri) {
+                None => ChangeKind::Created,
+                Some(previous) if previous != entry => ChangeKind::Modified,
+                Some(_) => continue,
+            };
+            events.push(WatchEvent {
+                kind,
+                data_uri: uri.clone(),
+                entry: *entry,
+            });
+        }
+        for (uri, entry) in &self.files {
+            if !other.files.contains_key(uri) {
+                events.push(WatchEvent {
+                    kind: ChangeKind::Deleted,
+                    data_uri: uri.clone(),
+                    entry: *entry,
+                });
+            }
+        }
+        events.sort_by(|a, b| a.data_uri.cmp(&b.data_uri));
+        events
+    }
+
+    fn apply(&mut self, event: &WatchEvent) {
+        match event.kind {
+            ChangeKind::Created | ChangeKind::Modified => {
+                self.files.insert(event.data_uri.clone(), event.entry);
+            }
+            ChangeKind::Deleted => {
+                self.files.remove(&event.data_uri);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{DataAcl, MemoryBackend};
+    use crate::Algorithmia;
+
+    fn changes(events: &[WatchEvent]) -> Vec<(ChangeKind, &str)> {
+        events
+            .iter()
+            .map(|e| (e.kind, e.data_uri.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_poll() {
+        let mut client = Algorithmia::client("").unwrap();
+        client.backend(MemoryBackend::new());
+        client.dir(".my/inbox").create(DataAcl::default()).unwrap();
+        client
+            .dir(".my/inbox/sub")
+            .create(DataAcl::default())
+            .unwrap();
+        client.file(".my/inbox/a.txt").put("a").unwrap();
+        client.file(".my/inbox/sub/b.txt").put("b").unwrap();
+
+        let mut watcher = client.dir(".my/inbox").watch();
+        let events = watcher.poll().unwrap();
+        assert_eq!(
+            changes(&events),
+            vec![(ChangeKind::Created, "data://.my/inbox/a.txt")]
+        );
+
+        watcher.recursive(true);
+        client.file(".my/inbox/a.txt").put("aa").unwrap();
+        let events = watcher.poll().unwrap();
+        assert_eq!(
+            changes(&events),
+            vec![
+                (ChangeKind::Modified, "data://.my/inbox/a.txt"),
+                (ChangeKind::Created, "data://.my/inbox/sub/b.txt"),
+            ]
+        );
+        assert!(watcher.poll().unwrap().is_empty());
+
+        // Resuming from a persisted snapshot only reports later changes
+        let json = serde_json::to_string(watcher.snapshot()).unwrap();
+        client.file(".my/inbox/a.txt").delete().unwrap();
+        let mut resumed = client.dir(".my/inbox").watch();
+        resumed
+            .recursive(true)
+            .interval(Duration::from_millis(0))
+            .resume_from(serde_json::from_str(&json).unwrap());
+        let event = resumed.next().unwrap().unwrap();
+        assert_eq!(event.kind, ChangeKind::Deleted);
+        assert_eq!(event.data_uri, "data://.my/inbox/a.txt");
+        assert_eq!(resumed.snapshot().files.len(), 1);
+    }
+}
@@ -0,0 +1,217 @@
+//! Streaming writer for uploading Algorithmia Data Files
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use algorithmia::Algorithmia;
+//! use std::io::Write;
+//!
+//! let client = Algorithmia::client("111112222233333444445555566")?;
+//! let mut writer = client.file(".my/my_dir/report.csv").writer();
+//!
+//! writeln!(writer, "id,value")?;
+//! for i in 0..1000 {
+//!     writeln!(writer, "{},{}", i, i * i)?;
+//! }
+//! writer.finish()?;
+//! # Ok::<(), Box<std::error::Error>>(())
+//! ```
+
+use crate::data::{DataFile, Direction, HasDataPath, ProgressTracker};
+use crate::error::{Error, ResultExt};
+use std::io::{self, Read, Write};
+use std::mem;
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::thread::{self, JoinHandle};
+
+/// Number of bytes buffered before a chunk is handed to the upload
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Number of chunks that may be queued for upload before writes block
+const PIPE_CAPACITY: usize = 4;
+
+enum Chunk {
+    Data(Vec<u8>),
+    Abort,
+}
+
+/// Writer that streams its bytes into the body of a `DataFile` upload
+///
+/// Created with [`DataFile::writer`](struct.DataFile.html#method.writer).
+/// Call `finish` to complete the upload and observe its result.
+/// Dropping a `FileWriter` without calling `finish` aborts the upload.
+pub struct FileWriter {
+    uri: String,
+    buf: Vec<u8>,
+    sender: Option<SyncSender<Chunk>>,
+    upload: Option<JoinHandle<Result<(), Error>>>,
+    tracker: Option<ProgressTracker>,
+}
+
+impl FileWriter {
+    pub(crate) fn new(file: DataFile) -> FileWriter {
+        let (sender, receiver) = mpsc::sync_channel(PIPE_CAPACITY);
+        let uri = file.to_data_uri();
+        let tracker = file.tracker(Direction::Upload, None, 0);
+        let upload = thread::spawn(move || {
+            let pipe = PipeReader {
+                receiver,
+                chunk: Vec::new(),
+                pos: 0,
+            };
+            file.put_reader(pipe)
+        });
+
+        FileWriter {
+            uri,
+            buf: Vec::with_capacity(CHUNK_SIZE),
+            sender: Some(sender),
+            upload: Some(upload),
+            tracker,
+        }
+    }
+
+    /// Flush any buffered bytes and wait for the upload to complete
+    pub fn finish(mut self) -> Result<(), Error> {
+        let sent = self.send_buf();
+        // Closing the pipe signals the end of the request body
+        self.sender = None;
+        // An upload error explains any failure to send the final chunk
+        self.join_upload()?;
+        if let Some(tracker) = &mut self.tracker {
+            tracker.finish();
+        }
+        sent.with_context(|| format!("request error writing file '{}'", self.uri))
+    }
+
+    fn send_buf(&mut self) -> io::Result<()> {
+        if self.buf.is_empty() {
+            return Ok(());
+        }
+        let chunk = mem::replace(&mut self.buf, Vec::with_capacity(CHUNK_SIZE));
+        let len = chunk.len() as u64;
+        match &self.sender {
+            Some(sender) => {
+                sender.send(Chunk::Data(chunk)).map_err(|_| {
+                    io::Error::new(
+                        io::ErrorKind::BrokenPipe,
+                        format!("upload of '{}' ended prematurely", self.uri),
+                    )
+                })?;
+                if let Some(tracker) = &mut self.tracker {
+                    tracker.advance(len);
+                }
+                Ok(())
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                format!("upload of '{}' already finished", self.uri),
+            )),
+        }
+    }
+
+    fn join_upload(&mut self) -> Result<(), Error> {
+        match self.upload.take() {
+            Some(upload) => match upload.join() {
+                Ok(res) => res,
+                Err(_) => bail!("upload of '{}' panicked", self.uri),
+            },
+            None => Ok(()),
+        }
+    }
+}
+
+impl Write for FileWriter {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        if self.buf.len() >= CHUNK_SIZE {
+            self.send_buf()?;
+        }
+        let n = data.len().min(CHUNK_SIZE - self.buf.len());
+        self.buf.extend_from_slice(&data[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.send_buf()
+    }
+}
+
+impl Drop for FileWriter {
+    fn drop(&mut self) {
+        if let Some(sender) = self.sender.take() {
+            // Fail the request body so a partial upload is never committed
+            let _ = sender.send(Chunk::Abort);
+            drop(sender);
+            let _ = self.join_upload();
+        }
+    }
+}
+
+/// Read half of the pipe between a `FileWriter` and its upload thread
+struct PipeReader {
+    receiver: Receiver<Chunk>,
+    chunk: Vec<u8>,
+    pos: usize,
+}
+
+impl Read for PipeReader {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        while self.pos >= self.chunk.len() {
+            match self.receiver.recv() {
+                Ok(Chunk::Data(chunk)) => {
+                    self.chunk = chunk;
+                    self.pos = 0;
+                }
+                Ok(Chunk::Abort) => {
+                    return Err(io::Error::other(
+                        "upload aborted before writer was finished",
+                    ))
+                }
+                // Writer finished and closed the pipe
+                Err(_) => return Ok(0),
+            }
+        }
+
+        let n = out.len().min(self.chunk.len() - self.pos);
+        out[..n].copy_from_slice(&self.chunk[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pipe() -> (SyncSender<Chunk>, PipeReader) {
+        let (sender, receiver) = mpsc::sync_channel(PIPE_CAPACITY);
+        let reader = PipeReader {
+            receiver,
+            chunk: Vec::new(),
+            pos: 0,
+        };
+        (sender, reader)
+    }
+
+    #[test]
+    fn test_pipe_reads_until_closed() {
+        let (sender, mut reader) = pipe();
+        sender.send(Chunk::Data(b"hello ".to_vec())).unwrap();
+        sender.send(Chunk::Data(b"world".to_vec())).unwrap();
+        drop(sender);
+
+        let mut text = String::new();
+        reader.read_to_string(&mut text).unwrap();
+        assert_eq!(text, "hello world");
+    }
+
+    #[test]
+    fn test_pipe_abort() {
+        let (sender, mut reader) = pipe();
+        sender.send(Chunk::Data(b"partial".to_vec())).unwrap();
+        sender.send(Chunk::Abort).unwrap();
+
+        let mut bytes = Vec::new();
+        assert!(reader.read_to_end(&mut bytes).is_err());
+    }
+}
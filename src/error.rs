@@ -70,6 +70,49 @@ impl Error {
             _ => None,
         }
     }
+
+    /// Whether retrying the failed operation might succeed (e.g. connection or server errors)
+    pub(crate) fn is_transient(&self) -> bool {
+        match &self.kind {
+            ErrorKind::Http(err, _) => match err.status() {
+                Some(status) => status.is_server_error(),
+                None => true,
+            },
+            ErrorKind::Inner(err) => match err.downcast_ref::<std::io::Error>() {
+                Some(io_err) => is_transient_io(io_err),
+                None => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Whether decrypting an encrypted data file failed because its content
+    /// did not authenticate (i.e. it was corrupted, truncated, or encrypted with a different key)
+    #[cfg(feature = "encryption")]
+    pub fn is_authentication_failure(&self) -> bool {
+        let err = match &self.kind {
+            ErrorKind::Inner(err) => err,
+            _ => return false,
+        };
+        match err.downcast_ref::<std::io::Error>() {
+            Some(io_err) => io_err
+                .get_ref()
+                .is_some_and(|inner| inner.is::<crate::data::AuthenticationError>()),
+            None => false,
+        }
+    }
+}
+
+/// Whether an I/O error is from the network side of a transfer, rather than e.g. the local disk
+fn is_transient_io(err: &std::io::Error) -> bool {
+    use std::io::ErrorKind::*;
+    match err.kind() {
+        ConnectionReset | ConnectionAborted | TimedOut | UnexpectedEof | Interrupted => true,
+        // Errors reading a response body
+        _ => err
+            .get_ref()
+            .is_some_and(|inner| inner.is::<reqwest::Error>()),
+    }
 }
 
 pub(crate) trait ResultExt<T> {
@@ -99,6 +142,12 @@ impl IntoErrorKind for reqwest::Error {
     }
 }
 
+impl IntoErrorKind for Box<dyn StdError + Send + Sync> {
+    fn into_error_kind(self) -> ErrorKind {
+        ErrorKind::Inner(self)
+    }
+}
+
 macro_rules! impl_into_error_kind {
     ($p:ty) => {
         impl IntoErrorKind for $p {
@@ -114,6 +163,10 @@ impl_into_error_kind!(serde_json::error::Error);
 impl_into_error_kind!(reqwest::header::InvalidHeaderValue);
 impl_into_error_kind!(url::ParseError);
 impl_into_error_kind!(base64::DecodeError);
+#[cfg(feature = "csv")]
+impl_into_error_kind!(csv::Error);
+#[cfg(feature = "archive")]
+impl_into_error_kind!(zip::result::ZipError);
 
 impl<T, E> ResultExt<T> for Result<T, E>
 where
@@ -22,9 +22,17 @@
 #![allow(unknown_lints)]
 #![recursion_limit = "1024"]
 
+#[cfg(feature = "csv")]
+extern crate csv_crate as csv;
+#[cfg(feature = "zstd")]
+extern crate zstd_crate as zstd;
+
 use crate::algo::{AlgoUri, Algorithm};
 use crate::client::HttpClient;
-use crate::data::{DataDir, DataFile, DataObject, HasDataPath};
+use crate::data::{
+    BulkDelete, ConnectorInfo, DataCache, DataDir, DataFile, DataObject, DataUri, Glob,
+    HasDataPath, StorageBackend, TempDataDir,
+};
 
 #[macro_use]
 pub mod error;
@@ -38,6 +46,7 @@ use crate::client::ApiAuth;
 use crate::error::Error;
 pub use reqwest::blocking::Body;
 pub use reqwest::{IntoUrl, Url};
+use std::sync::Arc;
 
 /// Reexports of the most common types and traits
 pub mod prelude {
@@ -104,6 +113,48 @@ impl Algorithmia {
         })
     }
 
+    /// Cache the content of data files downloaded with `DataFile::get_cached`
+    ///
+    /// Applies to data files and directories subsequently created from this client.
+    /// The cache may be shared by several clients (e.g. by cloning them after this call).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use algorithmia::Algorithmia;
+    /// use algorithmia::data::DataCache;
+    /// let mut client = Algorithmia::client("111112222233333444445555566")?;
+    /// client.cache(DataCache::new("/var/cache/algorithmia", 1024 * 1024 * 1024)?);
+    /// # Ok::<(), Box<std::error::Error>>(())
+    /// ```
+    pub fn cache(&mut self, cache: DataCache) -> &mut Algorithmia {
+        self.http_client.set_cache(Some(Arc::new(cache)));
+        self
+    }
+
+    /// Store data through a different storage backend than the Algorithmia Data API
+    ///
+    /// Applies to data files and directories subsequently created from this client,
+    /// so the same code can run against local or in-memory storage.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use algorithmia::Algorithmia;
+    /// use algorithmia::data::{DataAcl, MemoryBackend};
+    /// let mut client = Algorithmia::client("")?;
+    /// client.backend(MemoryBackend::new());
+    ///
+    /// client.dir(".my/my_dir").create(DataAcl::default())?;
+    /// client.file(".my/my_dir/hello.txt").put("hello")?;
+    /// assert_eq!(client.file(".my/my_dir/hello.txt").get()?.into_string()?, "hello");
+    /// # Ok::<(), Box<std::error::Error>>(())
+    /// ```
+    pub fn backend<B: StorageBackend + 'static>(&mut self, backend: B) -> &mut Algorithmia {
+        self.http_client.set_backend(Arc::new(backend));
+        self
+    }
+
     /// Instantiate an [`Algorithm`](algo/algorithm.struct.html) from this client
     ///
     /// By using In
@@ -129,8 +180,8 @@ impl Algorithmia {
     /// let rustfoo = client.dir("data://.my/rustfoo");
     /// # Ok::<(), Box<std::error::Error>>(())
     /// ```
-    pub fn dir(&self, path: &str) -> DataDir {
-        DataDir::new(self.http_client.clone(), path)
+    pub fn dir<P: Into<DataUri>>(&self, path: P) -> DataDir {
+        DataDir::new(self.http_client.clone(), &path.into().to_string())
     }
 
     /// Instantiate a `DataDirectory` from this client
@@ -139,12 +190,16 @@ impl Algorithmia {
     ///
     /// ```
     /// use algorithmia::Algorithmia;
+    /// use algorithmia::data::DataUri;
     /// let client = Algorithmia::client("111112222233333444445555566")?;
     /// let rustfoo = client.file("data://.my/rustfoo");
+    ///
+    /// let dir: DataUri = "data://.my/rustdir".parse()?;
+    /// let rustbar = client.file(dir.join("rustbar"));
     /// # Ok::<(), Box<std::error::Error>>(())
     /// ```
-    pub fn file(&self, path: &str) -> DataFile {
-        DataFile::new(self.http_client.clone(), path)
+    pub fn file<P: Into<DataUri>>(&self, path: P) -> DataFile {
+        DataFile::new(self.http_client.clone(), &path.into().to_string())
     }
 
     /// Instantiate a `DataPath` from this client
@@ -159,8 +214,87 @@ impl Algorithmia {
     /// let rustfoo = client.data("data://.my/rustfoo/what_am_i");
     /// # Ok::<(), Box<std::error::Error>>(())
     /// ```
-    pub fn data(&self, path: &str) -> DataObject {
-        DataObject::new(self.http_client.clone(), path)
+    pub fn data<P: Into<DataUri>>(&self, path: P) -> DataObject {
+        DataObject::new(self.http_client.clone(), &path.into().to_string())
+    }
+
+    /// List the data connectors configured for this account
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use algorithmia::Algorithmia;
+    /// let client = Algorithmia::client("111112222233333444445555566")?;
+    /// for connector in client.connectors()? {
+    ///     println!("{}: {}", connector.name, connector.connector);
+    /// }
+    /// # Ok::<(), Box<std::error::Error>>(())
+    /// ```
+    pub fn connectors(&self) -> Result<Vec<ConnectorInfo>, Error> {
+        data::list_connectors(&self.http_client)
+    }
+
+    /// Find the files and directories matching a glob pattern
+    ///
+    /// Supports `*` and `?` wildcards, `[...]` character classes, and `**` to match
+    /// any number of nested directories. Only directories that can match the pattern are listed.
+    /// A `\` matches the following character literally, e.g. `data://.my/\[draft\]/*`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use algorithmia::Algorithmia;
+    /// use algorithmia::data::{DataItem, HasDataPath};
+    /// let client = Algorithmia::client("111112222233333444445555566")?;
+    /// for item in client.glob("data://.my/runs/2026-*/**/*.csv") {
+    ///     if let DataItem::File(f) = item? {
+    ///         println!("{}", f.to_data_uri());
+    ///     }
+    /// }
+    /// # Ok::<(), Box<std::error::Error>>(())
+    /// ```
+    pub fn glob(&self, pattern: &str) -> Glob {
+        Glob::new(self.http_client.clone(), pattern)
+    }
+
+    /// Delete the files matching a glob pattern
+    ///
+    /// Returns a [`BulkDelete`](data/struct.BulkDelete.html) builder to add filters,
+    /// limit concurrency, or request a dry run before calling `run`.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use algorithmia::Algorithmia;
+    /// let client = Algorithmia::client("111112222233333444445555566")?;
+    /// let summary = client
+    ///     .bulk_delete("data://.my/runs/**/*.log")
+    ///     .dry_run(true)
+    ///     .run()?;
+    /// for uri in &summary.deleted {
+    ///     println!("would delete {}", uri);
+    /// }
+    /// # Ok::<(), Box<std::error::Error>>(())
+    /// ```
+    pub fn bulk_delete(&self, pattern: &str) -> BulkDelete {
+        BulkDelete::new(self.http_client.clone(), pattern)
+    }
+
+    /// Create a uniquely named directory in `data://.my` that is deleted when dropped
+    ///
+    /// The directory is named `tmp-` followed by a unique suffix.
+    /// See [`TempDataDir`](data/struct.TempDataDir.html).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use algorithmia::Algorithmia;
+    /// use algorithmia::data::{DataFile, HasDataPath};
+    /// let client = Algorithmia::client("111112222233333444445555566")?;
+    /// let scratch = client.temp_dir()?;
+    /// scratch.child::<DataFile>("result.json").put("{}")?;
+    /// # Ok::<(), Box<std::error::Error>>(())
+    /// ```
+    pub fn temp_dir(&self) -> Result<TempDataDir, Error> {
+        TempDataDir::new_in(&self.dir("data://.my"), "tmp-")
     }
 }
 